@@ -22,18 +22,18 @@ pub fn main() {
     // Convert a single scope in a policy that requires this scope
     let policy = ApiScope::SettingsReadonly.into_policy();
 
-    assert_eq!(true, policy.verify(&scopes));
+    assert!(policy.verify(&scopes));
 
     // Policies can also be combined together with &, | and !
     let other_policy = policy & ApiScope::ProfileReadonly;
 
     // With the hierarchy feature, "profile.readonly" is included in "profile", so this is accepted
     #[cfg(feature = "hierarchy")]
-    assert_eq!(true, other_policy.verify(&scopes));
+    assert!(other_policy.verify(&scopes));
 
     // Otherwise, the scopes require an exact match
     #[cfg(not(feature = "hierarchy"))]
-    assert_eq!(false, other_policy.verify(&scopes));
+    assert!(!other_policy.verify(&scopes));
 }
 
 // You would usually parse the scopes from a request in a middleware with
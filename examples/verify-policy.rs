@@ -27,7 +27,7 @@ pub fn main() {
     // Convert a single scope in a policy that requires this scope
     let admin_policy = ApiScope::Admin.into_policy();
 
-    assert_eq!(false, admin_policy.verify(&scopes));
+    assert!(!admin_policy.verify(&scopes));
 
 
     // You can use the PolicyBuilder to build complex policies
@@ -39,17 +39,17 @@ pub fn main() {
         )
         .build();
 
-    assert_eq!(true, complex_policy.verify(&scopes));
+    assert!(complex_policy.verify(&scopes));
 
     let more_scopes = [ApiScope::Profile, ApiScope::ContactsReadonly, ApiScope::MyCustomScope];
-    assert_eq!(false, complex_policy.verify(more_scopes));
+    assert!(!complex_policy.verify(more_scopes));
 
 
     // Policies can also be combined together with &, | and !
     
     let complex_or_admin = complex_policy | (admin_policy & !ApiScope::MyCustomScope.into_policy());
 
-    assert_eq!(true, complex_or_admin.verify([ApiScope::Admin]));
+    assert!(complex_or_admin.verify([ApiScope::Admin]));
 
 
     // With the `hierarchy` feature, scopes can include other ones, and will validate the policy
@@ -58,7 +58,7 @@ pub fn main() {
         let readonly_policy = ApiScope::ContactsReadonly.into_policy();
 
         // "contacts" includes "contacts.readonly"
-        assert_eq!(true, readonly_policy.verify([ApiScope::Contacts]));
+        assert!(readonly_policy.verify([ApiScope::Contacts]));
     }
 
 }
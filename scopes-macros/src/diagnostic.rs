@@ -0,0 +1,105 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, quote_spanned};
+use syn::Ident;
+
+// A compiler warning.
+//
+// Proc macros can't emit warnings on stable, so a warning is emitted as the use of a deprecated constant
+// with the span of the warning.
+pub struct Warning {
+    span: Span,
+    message: String,
+}
+
+impl Warning {
+    // Only the hierarchy emits warnings for now
+    #[cfg_attr(not(feature = "hierarchy"), allow(dead_code))]
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self { span, message: message.into() }
+    }
+
+    #[cfg(all(test, feature = "hierarchy"))]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl ToTokens for Warning {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let message = &self.message;
+        tokens.extend(quote_spanned! { self.span =>
+            const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const scope_warning: () = ();
+                scope_warning
+            };
+        });
+    }
+}
+
+// Get the number of single-character edits needed to change a string into another one
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+// Find the candidate closest to an unknown identifier, if it is close enough to be a typo
+pub fn closest_match<'a>(ident: &Ident, candidates: impl Iterator<Item = &'a Ident>) -> Option<&'a Ident> {
+    let name = ident.to_string();
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .map(|candidate| (edit_distance(&name.to_lowercase(), &candidate.to_string().to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// Error for an unknown variant used in an attribute, suggesting the closest variant
+pub fn unknown_variant<'a>(enum_ident: &Ident, ident: &Ident, attribute: &str, variants: impl Iterator<Item = &'a Ident>) -> syn::Error {
+    let mut message = format!("Unknown scope variant {}::{} in `{}`", enum_ident, ident, attribute);
+
+    if let Some(suggestion) = closest_match(ident, variants) {
+        message.push_str(&format!(", did you mean `{}`?", suggestion));
+    }
+
+    syn::Error::new(ident.span(), message)
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::diagnostic::{closest_match, edit_distance};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("foo", "foo"));
+        assert_eq!(1, edit_distance("foo", "fo"));
+        assert_eq!(1, edit_distance("foo", "fao"));
+        assert_eq!(3, edit_distance("foo", ""));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates: Vec<syn::Ident> = vec![parse_quote!(FooRead), parse_quote!(FooWrite), parse_quote!(Bar)];
+
+        assert_eq!(Some(&candidates[0]), closest_match(&parse_quote!(FooRaed), candidates.iter()));
+        assert_eq!(Some(&candidates[1]), closest_match(&parse_quote!(Foowrite), candidates.iter()));
+        assert_eq!(Some(&candidates[2]), closest_match(&parse_quote!(Baz), candidates.iter()));
+        assert_eq!(None, closest_match(&parse_quote!(Admin), candidates.iter()));
+    }
+}
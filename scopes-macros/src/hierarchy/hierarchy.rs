@@ -1,41 +1,397 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 
-use crate::{Scope, hierarchy::inclusion_graph::InclusionGraph};
+use crate::{Scope, diagnostic::{Warning, unknown_variant}, hierarchy::inclusion_graph::{InclusionGraph, label_inclusion}, scope::template::{labels_include, param_binding}};
+
+// Prefixes of the bindings of parameters in the generated `includes` function
+const SELF_PREFIX: &str = "self";
+const OTHER_PREFIX: &str = "other";
+
+// Get the parameters of an included scope that must be equal to the parameters of the including scope.
+//
+// When the labels of `scope` are a prefix of the labels of `included`, parameters are matched by position,
+// so `repo:{id}` only includes `repo:{id}:read` for the same id. Otherwise, for manual inclusions,
+// parameters are matched by name. The parameters of `included` that don't match any parameter
+// of `scope` are not constrained, which is only allowed with `allow_unbound_params`.
+fn param_constraints<'a>(scope: &'a Scope, included: &'a Scope) -> Vec<(&'a str, &'a str)> {
+
+    // The wrapped scope of a nested scope is not a parameter
+    if scope.nested.is_some() || included.nested.is_some() {
+        return Vec::new();
+    }
+
+    if labels_include(&scope.labels, &included.labels) {
+        let included_params: Vec<(usize, &str)> = included.params().collect();
+
+        scope.params()
+            .filter_map(|(i, param)| included_params.iter().find(|(j, _)| i == *j).map(|(_, other)| (param, *other)))
+            .collect()
+    } else {
+        scope.params()
+            .filter_map(|(_, param)| included.params().find(|(_, other)| *other == param).map(|(_, other)| (param, other)))
+            .collect()
+    }
+}
+
+// Check that the parameters of an included scope are bound to parameters of the same type in the including
+// scope, unless unbound parameters are allowed. Scopes without parameters include every parameter value
+fn validate_params(enum_ident: &Ident, target: &Ident, scope: &Scope, included: &Scope, allow_unbound: bool) -> Result<(), syn::Error> {
+
+    if scope.nested.is_some() || included.nested.is_some() || scope.params().next().is_none() {
+        return Ok(());
+    }
+
+    let constraints = param_constraints(scope, included);
+    let mut error: Option<syn::Error> = None;
+    let mut push_error = |msg: String| {
+        let err = syn::Error::new(target.span(), msg);
+        match error.as_mut() {
+            Some(error) => error.combine(err),
+            None => error = Some(err),
+        }
+    };
+
+    for (param, other) in &constraints {
+        if let (Some(ty), Some(other_ty)) = (scope.param_type(param), included.param_type(other))
+            && quote!(#ty).to_string() != quote!(#other_ty).to_string()
+        {
+            push_error(format!(
+                "The parameter `{{{}}}` has type `{}` in {}::{} and `{}` in {}::{}",
+                param, quote!(#ty), enum_ident, scope.ident, quote!(#other_ty), enum_ident, included.ident,
+            ));
+        }
+    }
+
+    if !allow_unbound {
+        for (_, param) in included.params().filter(|(_, p)| !constraints.iter().any(|(_, other)| other == p)) {
+            push_error(format!(
+                "The parameter `{{{}}}` of {}::{} is not bound to a parameter of {}::{}, which would include it for every value. \
+                Use the same parameter name, or add `allow_unbound_params` to include every value",
+                param, enum_ident, included.ident, enum_ident, scope.ident,
+            ));
+        }
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Check that included, parent, excluded and `child_include` scopes are variants of the enum, and warn about inclusions and exclusions
+// that have no effect
+pub fn validate_includes(enum_ident: &Ident, scopes: &[Scope], warnings: &mut Vec<Warning>) -> Result<(), syn::Error> {
+
+    let mut error: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match error.as_mut() {
+        Some(error) => error.combine(err),
+        None => error = Some(err),
+    };
+
+    let find = |ident: &Ident| scopes.iter().find(|s| &s.ident == ident);
+    let inclusion_graph = InclusionGraph::from_scopes(scopes.iter().filter(|s| !s.other));
+
+    for scope in scopes {
+
+        // Inclusions declared on the including scope with `include`, or on the included scope with `parent`,
+        // as (attribute, target, including scope, included scope)
+        let inclusions = scope.include.iter().map(|included| ("include", included, &scope.ident, included))
+            .chain(scope.parents.iter().map(|parent| ("parent", parent, parent, &scope.ident)));
+
+        for (attribute, target, including, included) in inclusions {
+            let (Some(including_scope), Some(included_scope)) = (find(including), find(included)) else {
+                push_error(unknown_variant(enum_ident, target, attribute, scopes.iter().map(|s| &s.ident)));
+                continue;
+            };
+
+            if including_scope.other || included_scope.other {
+                push_error(syn::Error::new(target.span(), format!("The `other` variant cannot be used in `{}`", attribute)));
+            } else if including_scope == included_scope {
+                warnings.push(Warning::new(
+                    target.span(),
+                    format!("{}::{} includes itself, which has no effect", enum_ident, included),
+                ));
+            } else if label_inclusion(including_scope, included_scope) {
+                warnings.push(Warning::new(
+                    target.span(),
+                    format!("{}::{} is already included in {}::{} by its labels", enum_ident, included, enum_ident, including),
+                ));
+            } else if let Err(err) = validate_params(enum_ident, target, including_scope, included_scope, scope.allow_unbound_params) {
+                push_error(err);
+            }
+        }
+
+        if scope.nested.is_none() && let Some((child, _)) = scope.child_include.first() {
+            push_error(syn::Error::new(child.span(), "`child_include` can only be used on nested scopes"));
+        }
+
+        for target in scope.child_include.iter().flat_map(|(_, targets)| targets) {
+            match find(target) {
+                None => push_error(unknown_variant(enum_ident, target, "child_include", scopes.iter().map(|s| &s.ident))),
+                Some(target_scope) if target_scope.other => push_error(syn::Error::new(
+                    target.span(),
+                    "The `other` variant cannot be used in `child_include`",
+                )),
+                Some(_) => (),
+            }
+        }
+
+        for excluded in &scope.exclude {
+            match scopes.iter().find(|s| &s.ident == excluded) {
+                None => push_error(unknown_variant(enum_ident, excluded, "exclude", scopes.iter().map(|s| &s.ident))),
+                Some(excluded_scope) if !scope.label_inclusions || !labels_include(&scope.labels, &excluded_scope.labels) || excluded_scope == scope => {
+                    warnings.push(Warning::new(
+                        excluded.span(),
+                        format!("{}::{} is not included in {}::{} by its labels, excluding it has no effect", enum_ident, excluded, enum_ident, scope.ident),
+                    ))
+                },
+
+                // The excluded scope can still be included through other inclusions
+                Some(_) => if let Some(path) = inclusion_graph.find_path(&scope.ident, excluded) {
+                    let path = path.iter().map(Ident::to_string).collect::<Vec<_>>().join(" -> ");
+                    warnings.push(Warning::new(
+                        excluded.span(),
+                        format!("{}::{} is excluded from {}::{} but still included through {}", enum_ident, excluded, enum_ident, scope.ident, path),
+                    ))
+                },
+            }
+        }
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Check that scope inclusions don't form cycles, unless they are allowed
+pub fn validate_hierarchy(scopes: &[Scope], allow_cycles: bool) -> Result<(), syn::Error> {
+
+    if allow_cycles {
+        return Ok(());
+    }
+
+    let scopes: Vec<&Scope> = scopes.iter().filter(|s| !s.other).collect();
+    let inclusion_graph = InclusionGraph::from_scopes(scopes.iter().copied());
+
+    let mut error: Option<syn::Error> = None;
+
+    // Each cycle is reported once, on its first declared scope
+    let mut skipped: HashSet<&Ident> = HashSet::new();
+
+    for scope in scopes {
+        if let Some(cycle) = inclusion_graph.find_cycle(&scope.ident, &skipped) {
+            let path = cycle.iter().map(Ident::to_string).collect::<Vec<_>>().join(" -> ");
+            let err = syn::Error::new(
+                scope.ident.span(),
+                format!("Inclusion cycle: {}. Use `#[scope(allow_cycles)]` on the enum to make these scopes equivalent", path),
+            );
+
+            match error.as_mut() {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
+            }
+        }
+
+        skipped.insert(&scope.ident);
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Get the scopes included in the given scopes, with the scopes included in the variants of the nested
+// scope enums with `child_include` when every variant of the nested scope is included
+fn included_scopes(inclusion_graph: &InclusionGraph, roots: impl Iterator<Item = syn::Ident>) -> Vec<syn::Ident> {
+    let mut included: Vec<syn::Ident> = Vec::new();
+    let mut to_visit: Vec<syn::Ident> = roots.collect();
+
+    while let Some(ident) = to_visit.pop() {
+        if included.contains(&ident) {
+            continue;
+        }
+
+        to_visit.extend(inclusion_graph.get_included_in(&ident).cloned());
+        if let Some(scope) = inclusion_graph.get(&ident) {
+            to_visit.extend(scope.child_include.iter().flat_map(|(_, targets)| targets.iter().cloned()));
+        }
+
+        included.push(ident);
+    }
+
+    included
+}
+
+// Get the expressions checking if the variable `other` is one of the included scopes,
+// and the parameters of `scope` used in these expressions
+fn inclusion_matches<'a>(enum_ident: &Ident, inclusion_graph: &InclusionGraph<'a>, scope: Option<&'a Scope>, included_idents: &[syn::Ident]) -> (Vec<TokenStream>, Vec<&'a str>) {
+
+    // Included variants without constraints on their parameters
+    let mut unconstrained_patterns: Vec<TokenStream> = Vec::new();
+
+    // Included variants with parameters equal to the parameters of this scope
+    let mut constrained_matches: Vec<TokenStream> = Vec::new();
+    let mut self_params: Vec<&str> = Vec::new();
+
+    for included_ident in included_idents {
+
+        // Unknown variants are kept so the compiler reports them
+        let Some(included) = inclusion_graph.get(included_ident) else {
+            unconstrained_patterns.push(quote! { #enum_ident::#included_ident });
+            continue;
+        };
+
+        let constraints = scope.map(|scope| param_constraints(scope, included)).unwrap_or_default();
+
+        if constraints.is_empty() {
+            unconstrained_patterns.push(included.fields.pattern(enum_ident, included_ident, &[], OTHER_PREFIX));
+            continue;
+        }
+
+        let other_params: Vec<&str> = constraints.iter().map(|(_, other)| *other).collect();
+        let pattern = included.fields.pattern(enum_ident, included_ident, &other_params, OTHER_PREFIX);
+        let self_bindings = constraints.iter().map(|(param, _)| param_binding(SELF_PREFIX, param));
+        let other_bindings = constraints.iter().map(|(_, other)| param_binding(OTHER_PREFIX, other));
+
+        constrained_matches.push(quote! {
+            matches!(other, #pattern if #(#self_bindings == #other_bindings)&&*)
+        });
+
+        for (param, _) in constraints {
+            if !self_params.contains(&param) {
+                self_params.push(param);
+            }
+        }
+    }
+
+    // Convert the matched enum variant names to a match pattern :
+    // [VariantA, VariantB] becomes `EnumName::VariantA | EnumName::VariantB`
+    let mut matches = constrained_matches;
+    if !unconstrained_patterns.is_empty() {
+        matches.insert(0, quote! { matches!(other, #(#unconstrained_patterns)|*) });
+    }
+
+    (matches, self_params)
+}
+
+// Get the rows of a bit matrix with `width` columns, where the bits at the given indices are set
+pub fn bitmask(indices: &[usize], width: usize) -> Vec<u64> {
+    let mut words = vec![0u64; width.div_ceil(64).max(1)];
+
+    for index in indices {
+        words[index / 64] |= 1 << (index % 64);
+    }
+
+    words
+}
+
+// Implement the Hierarchized trait.
+//
+// With `inclusion_table`, the inclusions without constraints on the parameters are stored in a bit matrix
+// indexed by the variant indices, and only the constrained inclusions are checked with a match.
+pub fn implement_hierarchized(enum_ident: &Ident, scopes: &[Scope], inclusion_table: bool) -> TokenStream {
+
+    // Index of each variant, as returned by the generated `index()` function
+    let indices: Vec<(&Ident, usize)> = Scope::index_order(scopes).into_iter().enumerate().map(|(i, s)| (&s.ident, i)).collect();
+    let index_of = |ident: &Ident| indices.iter().find(|(i, _)| *i == ident).map(|(_, index)| *index);
+    let scopes_count = scopes.len();
+
+    // The catch-all variant only includes itself
+    let scopes: Vec<&Scope> = scopes.iter().filter(|s| !s.other).collect();
 
-// Implement the Hierarchized trait
-pub fn implement_hierarchized(enum_ident: &Ident, scopes: &HashMap<String, Scope>) -> TokenStream {
-    
     // Construct the inclusion graph
-    let inclusion_graph = InclusionGraph::from_scopes(scopes.values());
+    let inclusion_graph = InclusionGraph::from_scopes(scopes.iter().copied());
+
+    // Nested scopes include the scopes included by the wrapped scope, and the scopes included
+    // in the variants of the wrapped scope enum with `child_include`
+    let mut nested_checks: Vec<TokenStream> = Vec::new();
+
+    for scope in scopes.iter() {
+        let Some(ty) = &scope.nested else {
+            continue;
+        };
+
+        let self_pattern = scope.fields.pattern(enum_ident, &scope.ident, &["0"], SELF_PREFIX);
+        let other_pattern = scope.fields.pattern(enum_ident, &scope.ident, &["0"], OTHER_PREFIX);
+        let self_binding = param_binding(SELF_PREFIX, "0");
+        let other_binding = param_binding(OTHER_PREFIX, "0");
+
+        nested_checks.push(quote! {
+            if let (#self_pattern, #other_pattern) = (self, other) {
+                if ::scopes_rs::hierarchy::Hierarchized::includes(#self_binding, #other_binding) {
+                    return true;
+                }
+            }
+        });
+
+        for (child, targets) in &scope.child_include {
+            let included = included_scopes(&inclusion_graph, targets.iter().cloned());
+            let (matches, _) = inclusion_matches(enum_ident, &inclusion_graph, None, &included);
+
+            nested_checks.push(quote! {
+                if let #self_pattern = self {
+                    if ::scopes_rs::hierarchy::Hierarchized::includes(#self_binding, &<#ty>::#child) && (#(#matches)||*) {
+                        return true;
+                    }
+                }
+            });
+        }
+    }
 
     // Construct the iterators that maps each scope with the ones it includes
 
-    let mut scope_idents: Vec<&Ident> = Vec::new();
-    let mut match_patterns: Vec<TokenStream> = Vec::new();
+    let mut scope_patterns: Vec<TokenStream> = Vec::new();
+    let mut match_expressions: Vec<TokenStream> = Vec::new();
+
+    // Rows of the inclusion table, by variant index
+    let mut table_rows: Vec<Vec<u64>> = vec![bitmask(&[], scopes_count); scopes_count];
+
+    for scope in scopes {
+
+        let mut included = included_scopes(&inclusion_graph, inclusion_graph.get_included_in(&scope.ident).cloned());
+
+        if inclusion_table && let Some(index) = index_of(&scope.ident) {
+            let (unconstrained, constrained): (Vec<_>, Vec<_>) = included.into_iter().partition(|ident| {
+                inclusion_graph.get(ident).is_some_and(|included| param_constraints(scope, included).is_empty())
+            });
+
+            let unconstrained_indices: Vec<usize> = unconstrained.iter().filter_map(index_of).collect();
+            table_rows[index] = bitmask(&unconstrained_indices, scopes_count);
+            included = constrained;
+        }
 
-    for scope in inclusion_graph.nodes() {
-        
         // If no scope is included in this one, skip it
-        if !inclusion_graph.has_neighbors(scope) {
+        if included.is_empty() {
             continue;
         }
 
-        let matched_variants = inclusion_graph.get_included_in(scope);
-
-        // Convert the matched enum variant names to a match pattern :
-        // [VariantA, VariantB] becomes `EnumName::VariantA | EnumName::VariantB`
-        let match_pattern = quote! {
-            #(#enum_ident::#matched_variants)|*
-        };
+        let (matches, self_params) = inclusion_matches(enum_ident, &inclusion_graph, Some(scope), &included);
 
-        scope_idents.push(scope);
-        match_patterns.push(match_pattern);
+        scope_patterns.push(scope.fields.pattern(enum_ident, &scope.ident, &self_params, SELF_PREFIX));
+        match_expressions.push(quote! { #(#matches)||* });
     }
 
+    let table_check = if inclusion_table {
+        let words = scopes_count.div_ceil(64).max(1);
+        let rows = table_rows.iter().map(|row| quote! { [#(#row),*] });
+
+        quote! {
+            const INCLUSIONS: [[u64; #words]; #scopes_count] = [#(#rows),*];
+
+            let included = other.index();
+            if INCLUSIONS[self.index()][included / 64] & (1 << (included % 64)) != 0 {
+                return true;
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     quote! {
         impl ::scopes_rs::hierarchy::Hierarchized for #enum_ident {
             fn includes(&self, other: &Self) -> bool {
@@ -43,12 +399,16 @@ pub fn implement_hierarchized(enum_ident: &Ident, scopes: &HashMap<String, Scope
                     return true;
                 }
 
+                #(#nested_checks)*
+
+                #table_check
+
                 match self {
-                    #(#enum_ident::#scope_idents => matches!(other, #match_patterns),)*
+                    #(#scope_patterns => #match_expressions,)*
                     _ => false
                 }
             }
         }
 
-    }.into()
+    }
 }
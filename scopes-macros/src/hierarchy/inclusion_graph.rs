@@ -1,8 +1,17 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::Scope;
+use crate::{Scope, scope::template::labels_include};
 
 
+// Check if a scope includes another one by its labels, unless the inclusion is disabled or excluded.
+// The scopes included in a nested scope are defined by the wrapped scope enum.
+pub fn label_inclusion(scope: &Scope, other: &Scope) -> bool {
+    scope.label_inclusions
+        && scope.nested.is_none()
+        && !scope.exclude.contains(&other.ident)
+        && labels_include(&scope.labels, &other.labels)
+}
+
 // A graph representing scope inclusions
 pub struct InclusionGraph<'a> {
     // Adjacency list for each node
@@ -44,11 +53,11 @@ impl<'a> InclusionGraph<'a> {
             }
 
             // other_scope is included in scope
-            if other_scope.labels.starts_with(&scope.labels) {
+            if label_inclusion(scope, other_scope) {
                 includes.push(&other_scope.ident);
             
             // scope is included in other_scope
-            } else if scope.labels.starts_with(&other_scope.labels) {
+            } else if label_inclusion(other_scope, scope) {
                 included_in.push(&other_scope.ident);
             }
 
@@ -63,9 +72,14 @@ impl<'a> InclusionGraph<'a> {
         }
 
 
-        // Add manually included scopes
-        for other in &scope.include {
-            self.add_inclusion(&scope.ident, &other);
+        // Add manually included scopes, a scope always includes itself
+        for other in scope.include.iter().filter(|other| *other != &scope.ident) {
+            self.add_inclusion(&scope.ident, other);
+        }
+
+        // Add this scope to its parents
+        for parent in scope.parents.iter().filter(|parent| *parent != &scope.ident) {
+            self.add_inclusion(parent, &scope.ident);
         }
         // Add the scope to the resolved nodes if it wasn't resolved already
         self.resolved_nodes.insert(scope.ident.clone(), scope);
@@ -94,13 +108,69 @@ impl<'a> InclusionGraph<'a> {
         DfsIterator::new(self, scope)
     }
 
-    // Iterate over the nodes
-    pub fn nodes(&self) -> impl Iterator<Item = &syn::Ident> {
-        self.neighbors.keys()
+
+    // Get the scope corresponding to a node, if it was added to the graph
+    pub fn get(&self, ident: &syn::Ident) -> Option<&'a Scope> {
+        self.resolved_nodes.get(ident).copied()
     }
 
-    pub fn has_neighbors(&self, scope: &syn::Ident) -> bool {
-        return self.neighbors.get(scope).map(|n| !n.is_empty()).unwrap_or_default()
+    // Find a path of inclusions going from a scope back to itself, without going through the skipped scopes.
+    // The returned path starts and ends with the given scope.
+    pub fn find_cycle(&self, start: &syn::Ident, skipped: &HashSet<&syn::Ident>) -> Option<Vec<syn::Ident>> {
+        let mut visited = HashSet::new();
+        let mut path = vec![start.clone()];
+
+        if self.find_path_to(start, start, skipped, &mut visited, &mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    // Find a path of inclusions going from a scope to another one. The returned path starts with `from`
+    // and ends with `to`.
+    pub fn find_path(&self, from: &syn::Ident, to: &syn::Ident) -> Option<Vec<syn::Ident>> {
+        let mut visited = HashSet::new();
+        let mut path = vec![from.clone()];
+
+        if self.find_path_to(from, to, &HashSet::new(), &mut visited, &mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    // Depth-first search of a path to the target scope, visiting neighbors by name for a stable result
+    fn find_path_to<'b>(
+        &'b self,
+        current: &syn::Ident,
+        target: &syn::Ident,
+        skipped: &HashSet<&syn::Ident>,
+        visited: &mut HashSet<&'b syn::Ident>,
+        path: &mut Vec<syn::Ident>,
+    ) -> bool {
+
+        let mut neighbors: Vec<&syn::Ident> = self.neighbors.get(current).map(|n| n.iter().collect()).unwrap_or_default();
+        neighbors.sort_by_key(|ident| ident.to_string());
+
+        for neighbor in neighbors {
+            if neighbor == target {
+                path.push(neighbor.clone());
+                return true;
+            }
+
+            if skipped.contains(neighbor) || !visited.insert(neighbor) {
+                continue;
+            }
+
+            path.push(neighbor.clone());
+            if self.find_path_to(neighbor, target, skipped, visited, path) {
+                return true;
+            }
+            path.pop();
+        }
+
+        false
     }
 }
 
@@ -129,10 +199,7 @@ impl<'a> Iterator for DfsIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         
-        let current = match self.to_visit.pop() {
-            Some(ident) => ident,
-            None => return None,
-        };
+        let current = self.to_visit.pop()?;
 
         if self.visited.contains(current) {
             return self.next();
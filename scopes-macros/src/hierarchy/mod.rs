@@ -1,5 +1,6 @@
+#[allow(clippy::module_inception)]
 mod hierarchy;
-pub(self) mod inclusion_graph;
+mod inclusion_graph;
 
 #[cfg(test)]
 mod tests;
@@ -2,14 +2,14 @@
 
 use proc_macro2::Span;
 
-use crate::{Scope, hierarchy::{inclusion_graph::InclusionGraph}};
+use crate::{Scope, diagnostic::Warning, hierarchy::{bitmask, inclusion_graph::InclusionGraph, validate_hierarchy, validate_includes}};
 
 // Create a new scope with the given identifier name and labels.
 // The separator is "." and the prefix is empty.
 fn new_scope(ident_name: &str, labels: &[&str]) -> Scope {
     Scope::_test_new(
         syn::Ident::new(ident_name, Span::call_site()),
-        labels.into_iter(),
+        labels.iter(),
         ".",
         "",
     )
@@ -75,4 +75,165 @@ fn test_non_inclusion() {
     assert!(!is_included(&g, &scope_foo_baz_baz, &scope_foo_bar));
     assert!(!is_included(&g, &scope_foo_bar, &scope_bar));
     assert!(!is_included(&g, &scope_bar, &scope_foo_bar));
-}
\ No newline at end of file
+}
+#[test]
+fn test_inclusion_cycle() {
+    let ident = |name: &str| syn::Ident::new(name, Span::call_site());
+    let scopes = |foo_bar_include: &[&str], baz_include: &[&str]| {
+        let scope_foo = new_scope("Foo", &["foo"]);
+        let mut scope_foo_bar = new_scope("FooBar", &["foo", "bar"]);
+        let mut scope_baz = new_scope("Baz", &["baz"]);
+        scope_foo_bar.include = foo_bar_include.iter().map(|i| ident(i)).collect();
+        scope_baz.include = baz_include.iter().map(|i| ident(i)).collect();
+        [scope_foo, scope_foo_bar, scope_baz]
+    };
+
+    assert!(validate_hierarchy(&scopes(&["Baz"], &[]), false).is_ok());
+
+    // Cycle mixing a label inclusion and manual inclusions
+    let err = validate_hierarchy(&scopes(&["Baz"], &["Foo"]), false).expect_err("Cycle should be detected");
+    assert!(err.to_string().starts_with("Inclusion cycle: Foo -> FooBar -> Baz -> Foo."), "{}", err);
+    assert_eq!(1, err.into_iter().count());
+
+    assert!(validate_hierarchy(&scopes(&["Baz"], &["Foo"]), true).is_ok());
+
+    // Self inclusions are not cycles
+    assert!(validate_hierarchy(&scopes(&[], &["Baz"]), false).is_ok());
+}
+
+#[test]
+fn test_validate_includes() {
+    let enum_ident = syn::Ident::new("MyScope", Span::call_site());
+    let ident = |name: &str| syn::Ident::new(name, Span::call_site());
+    let scopes = |foo_include: &str| {
+        let mut scope_foo = new_scope("Foo", &["foo"]);
+        scope_foo.include.push(ident(foo_include));
+        [scope_foo, new_scope("FooBar", &["foo", "bar"]), new_scope("Baz", &["baz"])]
+    };
+
+    let mut warnings = Vec::new();
+    assert!(validate_includes(&enum_ident, &scopes("Baz"), &mut warnings).is_ok());
+    assert!(warnings.is_empty());
+
+    let err = validate_includes(&enum_ident, &scopes("Bar"), &mut warnings).expect_err("Unknown variant should be rejected");
+    assert_eq!("Unknown scope variant MyScope::Bar in `include`, did you mean `Baz`?", err.to_string());
+
+    let err = validate_includes(&enum_ident, &scopes("Admin"), &mut warnings).expect_err("Unknown variant should be rejected");
+    assert_eq!("Unknown scope variant MyScope::Admin in `include`", err.to_string());
+
+    assert!(validate_includes(&enum_ident, &scopes("Foo"), &mut warnings).is_ok());
+    assert!(validate_includes(&enum_ident, &scopes("FooBar"), &mut warnings).is_ok());
+    let messages: Vec<&str> = warnings.iter().map(Warning::message).collect();
+    assert_eq!(vec![
+        "MyScope::Foo includes itself, which has no effect",
+        "MyScope::FooBar is already included in MyScope::Foo by its labels",
+    ], messages);
+}
+
+#[test]
+fn test_validate_unbound_params() {
+    let enum_ident = syn::Ident::new("MyScope", Span::call_site());
+    let scopes = |org_labels: &[&str], allow_unbound: bool| {
+        let mut scope_org = new_scope("Org", org_labels);
+        scope_org.include.push(syn::Ident::new("Repo", Span::call_site()));
+        scope_org.allow_unbound_params = allow_unbound;
+        scope_org.param_types = vec![("org".to_string(), syn::parse_quote!(String))];
+
+        let mut scope_repo = new_scope("Repo", &["repo", "{org}", "{repo}"]);
+        scope_repo.param_types = vec![("org".to_string(), syn::parse_quote!(u64)), ("repo".to_string(), syn::parse_quote!(String))];
+        [scope_org, scope_repo, new_scope("Admin", &["admin"])]
+    };
+
+    let mut warnings = Vec::new();
+    let err = validate_includes(&enum_ident, &scopes(&["org", "{org}"], false), &mut warnings).expect_err("Unbound parameters should be rejected");
+    let messages: Vec<String> = err.into_iter().map(|e| e.to_string()).collect();
+    assert_eq!(vec![
+        "The parameter `{org}` has type `String` in MyScope::Org and `u64` in MyScope::Repo",
+        "The parameter `{repo}` of MyScope::Repo is not bound to a parameter of MyScope::Org, which would include it for every value. \
+        Use the same parameter name, or add `allow_unbound_params` to include every value",
+    ], messages);
+
+    // Unbound parameters can be allowed, but the types of the bound parameters are still checked
+    let err = validate_includes(&enum_ident, &scopes(&["org", "{org}"], true), &mut warnings).expect_err("Mismatched types should be rejected");
+    assert_eq!("The parameter `{org}` has type `String` in MyScope::Org and `u64` in MyScope::Repo", err.to_string());
+
+    // Scopes without parameters include every parameter value
+    assert!(validate_includes(&enum_ident, &scopes(&["org"], false), &mut warnings).is_ok());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_exclusion() {
+    let scope_admin = new_scope("Admin", &["admin"]);
+    let scope_admin_users = new_scope("AdminUsers", &["admin", "users"]);
+    let scope_admin_audit_log = new_scope("AdminAuditLog", &["admin", "audit_log"]);
+    let mut scope_admin_excluding = new_scope("Admin", &["admin"]);
+    scope_admin_excluding.exclude.push(scope_admin_audit_log.ident.clone());
+
+    let g = InclusionGraph::from_scopes([&scope_admin, &scope_admin_users, &scope_admin_audit_log].into_iter());
+    assert!(is_included(&g, &scope_admin_audit_log, &scope_admin));
+
+    let g = InclusionGraph::from_scopes([&scope_admin_excluding, &scope_admin_users, &scope_admin_audit_log].into_iter());
+    assert!(is_included(&g, &scope_admin_users, &scope_admin));
+    assert!(!is_included(&g, &scope_admin_audit_log, &scope_admin));
+
+    // The order of the scopes doesn't matter
+    let g = InclusionGraph::from_scopes([&scope_admin_audit_log, &scope_admin_users, &scope_admin_excluding].into_iter());
+    assert!(is_included(&g, &scope_admin_users, &scope_admin));
+    assert!(!is_included(&g, &scope_admin_audit_log, &scope_admin));
+}
+
+#[test]
+fn test_validate_exclusion_path() {
+    let enum_ident = syn::Ident::new("MyScope", Span::call_site());
+    let mut scope_admin = new_scope("Admin", &["admin"]);
+    scope_admin.exclude.push(syn::Ident::new("AdminAuditLog", Span::call_site()));
+    let scopes = [scope_admin, new_scope("AdminAudit", &["admin", "audit"]), new_scope("AdminAuditLog", &["admin", "audit", "log"])];
+
+    let mut warnings = Vec::new();
+    assert!(validate_includes(&enum_ident, &scopes, &mut warnings).is_ok());
+    let messages: Vec<&str> = warnings.iter().map(Warning::message).collect();
+    assert_eq!(vec![
+        "MyScope::AdminAuditLog is excluded from MyScope::Admin but still included through Admin -> AdminAudit -> AdminAuditLog",
+    ], messages);
+}
+
+#[test]
+fn test_disabled_label_inclusions() {
+    let mut scope_foo = new_scope("Foo", &["foo"]);
+    let mut scope_foo_bar = new_scope("FooBar", &["foo", "bar"]);
+    let mut scope_foo_baz = new_scope("FooBaz", &["foo", "baz"]);
+    scope_foo.include.push(scope_foo_baz.ident.clone());
+
+    for scope in [&mut scope_foo, &mut scope_foo_bar, &mut scope_foo_baz] {
+        scope.label_inclusions = false;
+    }
+
+    // Manual inclusions still apply
+    let g = InclusionGraph::from_scopes([&scope_foo, &scope_foo_bar, &scope_foo_baz].into_iter());
+    assert!(!is_included(&g, &scope_foo_bar, &scope_foo));
+    assert!(is_included(&g, &scope_foo_baz, &scope_foo));
+}
+
+#[test]
+fn test_parent() {
+    let scope_org_owner = new_scope("OrgOwner", &["org", "owner"]);
+    let scope_billing = new_scope("Billing", &["billing"]);
+    let mut scope_billing_manage = new_scope("BillingManage", &["billing", "manage"]);
+    scope_billing_manage.parents.push(scope_org_owner.ident.clone());
+
+    // The parent can be declared after its children
+    let g = InclusionGraph::from_scopes([&scope_billing, &scope_billing_manage, &scope_org_owner].into_iter());
+
+    assert!(is_included(&g, &scope_billing_manage, &scope_org_owner));
+    assert!(is_included(&g, &scope_billing_manage, &scope_billing));
+    assert!(!is_included(&g, &scope_billing, &scope_org_owner));
+    assert!(!is_included(&g, &scope_org_owner, &scope_billing_manage));
+}
+
+#[test]
+fn test_bitmask() {
+    assert_eq!(vec![0], bitmask(&[], 0));
+    assert_eq!(vec![0b101], bitmask(&[0, 2], 3));
+    assert_eq!(vec![1 << 63, 0b10], bitmask(&[63, 65], 70));
+}
@@ -17,19 +17,66 @@ mod hierarchy;
 
 mod scope;
 
+mod diagnostic;
+
 /// ## Optional `#[scope(...)]` attributes for the enum
 /// 
 /// - `separator = "..."`: Change the separator between scope labels. Defaults to `"."`
+/// - `rename_all = "..."`: Naming convention for the inferred scope names, one of `"dot.case"` (default), `"snake_case"`,
+///   `"kebab-case"`, `"colon:case"`, `"SCREAMING_SNAKE"` or `"verbatim"`. Cannot be used with `separator`
+/// - `split = "..."`: How variant names are split in labels. `"uppercase"` (default) splits at each uppercase letter,
+///   `"acronyms"` keeps acronyms and digits together
 /// - `prefix = "..."`: Add a prefix to every generated scope name. Default is an empty prefix
 /// - `hierarchy = bool`: Enable or disable generation of the `Hierarchized` trait. Requires the `hierarchy`
-///     feature. Defaults to `true`.
+///   feature. Defaults to `true`.
+/// - `allow_cycles`: Allow scopes to include each other, which makes them equivalent. Requires the `hierarchy` feature
+/// - `infer_hierarchy = bool`: Include scopes in the scopes whose labels are a prefix of their labels. When disabled,
+///   only the `include` lists are used. Requires the `hierarchy` feature. Defaults to `true`
+/// - `inclusion_table`: Store the inclusions in a bit matrix indexed by the variant indices, instead of matching every
+///   included scope. Recommended for enums with many scopes. Requires the `hierarchy` feature
 /// - `scope_name_getter = bool`: Implement the `scope_name()` function to get the scope name from a variant (defaults to true)
+/// - `display`: Implement `Display` with the scope name. Not implemented by default, so the enum can implement `Display` itself
+/// - `case_insensitive`: Ignore the ASCII case and surrounding whitespace when parsing scopes
+/// - `normalize = path::to::function`: Call a function on the input before parsing it. The function takes a `&str`
+///   and returns a type implementing `AsRef<str>`
+/// - `wildcard`: Implement `WildcardScope` to accept wildcard grants like `foo.*` or `*`. Requires a non-empty separator
+/// - `validate_names = bool`: Check that every scope name is a valid OAuth 2.0 scope token, which only contains
+///   printable ASCII characters except spaces, `"` and `\`. Defaults to `true`. Empty names and empty labels are
+///   always rejected
 /// 
 /// ## Optional `#[scope(...)]` attributes for enum variants
 /// 
-/// - `rename = "..."`: Use a specific name instead of inferring it from the variant name
+/// - `rename = "..."`: Use a specific name instead of inferring it from the variant name. Required for
+///   variants with fields, where it is a template with a parameter label for each field, like `"repo.{0}"`
+/// - `alias = "..."`: Accept another name when parsing the scope. Can be repeated
+/// - `description = "..."`: A human-readable description of the scope. Defaults to the doc comment of the variant
+/// - `title = "..."`, `category = "..."`, `icon = "..."`: Additional metadata for the scope
+/// - `internal` or `skip_parse`: Never parse this scope from a string. It can still be used in policies and in the
+///   hierarchy, but is not listed by `scopes_supported()` and is never granted by a wildcard
+/// - `default`: Grant this scope when no scope is requested. Default scopes are listed by the generated `defaults()`
+///   function
+/// - `requires = scope | [scope1, ...]`: Scopes that must be requested with this scope, checked by the generated
+///   `validate_request()` function. Required scopes cannot have fields
+/// - `conflicts_with = scope | [scope1, ...]`: Scopes that cannot be requested with this scope, checked by the
+///   generated `validate_request()` function
+/// - `expands_to = scope | [scope1, ...]`: Make this scope a group, replaced by its members when parsing a
+///   `ScopeList`. Groups and their members cannot have fields, groups cannot expand to themselves, and only internal
+///   groups can have internal members
+/// - `other`: Use this variant for unknown scopes instead of failing to parse them. The variant must have a single
+///   field implementing `From<&str>` and `Display`, like `Unknown(String)`
+/// - `nested`: Wrap the scopes of another scope enum, like `Billing(BillingScope)`. The names of the wrapped scopes are
+///   prefixed with the labels of the variant, like `billing.invoices.read`. The variant must have a single field
+/// - `child_include(Child = scope | [scope1, ...])`: On a nested scope, include other scopes of the enum in the variant
+///   wrapping `Child` and in the variants wrapping a scope including `Child`. Requires the `hierarchy` feature
 /// - `include = scope | [scope1, ...]`: Include other scopes in the hierarchy. See below for more details.
-///     requires the `hierarchy` feature
+///   requires the `hierarchy` feature
+/// - `parent = scope | [scope1, ...]`: Include this scope in other scopes, like `include` on the parent scopes.
+///   Requires the `hierarchy` feature
+/// - `allow_unbound_params`: Allow `include` and `parent` to include a parameterized scope with parameters that don't
+///   correspond to a parameter of the including scope, for every value. Requires the `hierarchy` feature
+/// - `exclude = scope | [scope1, ...]`: Don't include scopes in this scope by their labels. They are still included
+///   if they are in the `include` list, or included by another scope included in this one, which emits a warning
+///   naming the inclusion path. Requires the `hierarchy` feature
 /// 
 /// ### Hierarchy customization
 /// 
@@ -68,6 +115,43 @@ mod scope;
 /// // since FooReadonly includes it
 /// assert!(MyScope::Readonly.includes(&MyScope::FooBarReadonly))
 /// ```
+/// 
+/// Inclusions cannot form a cycle, like a scope including one of its parents, since every scope of the cycle
+/// would include the others. The compilation fails with the path of the cycle, unless the enum is marked with
+/// `#[scope(allow_cycles)]`.
+/// 
+/// The inclusion can also be declared on the included scope with `#[scope(parent = ...)]`, which has the same
+/// effect as `include` on the parent scope : `#[scope(parent = Readonly)] BarReadonly` is equivalent to
+/// `#[scope(include = BarReadonly)] Readonly`.
+/// 
+/// Scopes are also included in the scopes whose labels are a prefix of their labels. A scope can opt out of
+/// some of these inclusions with `#[scope(exclude = ...)]`, and the enum can disable them entirely with
+/// `#[scope(infer_hierarchy = false)]`.
+/// 
+/// Included scopes must be variants of the enum, other than the `other` variant. The derive macro warns about
+/// inclusions that have no effect, like a scope including itself or a scope already included by its labels.
+/// 
+/// By default, `includes` matches the other scope against every scope included in this one, which generates a lot
+/// of code for large enums. With `#[scope(inclusion_table)]` on the enum, the inclusions are stored in a constant
+/// table of bitmasks indexed by `index()`, so checking an inclusion takes constant time. Inclusions between
+/// parameterized scopes that depend on the parameter values, like `repo.{0}` including `repo.{0}.read`, are still
+/// checked with a match.
+/// 
+/// A nested scope includes the nested scopes of the same variant that its wrapped scope includes, and is
+/// included in the scopes whose labels are a prefix of the variant labels. Scopes of other variants can be
+/// included in a nested scope with `child_include` :
+/// 
+/// ```ignore
+/// #[derive(Clone, Debug, PartialEq, Scope)]
+/// enum AppScope {
+///     #[scope(nested, child_include(Root = Billing))]
+///     Admin(AdminScope),
+///     #[scope(nested)]
+///     Billing(BillingScope),
+/// }
+/// 
+/// assert!(AppScope::Admin(AdminScope::Root).includes(&AppScope::Billing(BillingScope::InvoicesRead)));
+/// ```
 #[proc_macro_derive(Scope, attributes(scope))]
 pub fn derive_into_scope(item: TokenStream) -> TokenStream {
     
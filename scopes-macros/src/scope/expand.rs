@@ -0,0 +1,196 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::{Scope, diagnostic::unknown_variant, scope::template::{CONSTRUCTOR_PREFIX, param_binding}};
+
+// Check that the members of groups are valid variants, that parsed groups don't expand to internal scopes,
+// and that groups don't expand to themselves
+pub fn validate_groups(enum_ident: &Ident, scopes: &[Scope]) -> Result<(), syn::Error> {
+
+    let mut error: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match error.as_mut() {
+        Some(error) => error.combine(err),
+        None => error = Some(err),
+    };
+
+    let find = |ident: &Ident| scopes.iter().find(|s| &s.ident == ident);
+
+    for scope in scopes.iter().filter(|s| !s.expands_to.is_empty()) {
+
+        if !scope.fields.is_unit() {
+            push_error(syn::Error::new(scope.ident.span(), "Groups of scopes cannot have fields"));
+        }
+
+        for member in &scope.expands_to {
+            match find(member) {
+                None => push_error(unknown_variant(enum_ident, member, "expands_to", scopes.iter().map(|s| &s.ident))),
+                Some(member_scope) if member_scope.other => push_error(syn::Error::new(
+                    member.span(),
+                    "The `other` variant cannot be used in `expands_to`",
+                )),
+                Some(member_scope) if !member_scope.fields.is_unit() => push_error(syn::Error::new(
+                    member.span(),
+                    "Members of a group cannot have fields",
+                )),
+
+                // Parsed groups must not expand to scopes that can't be parsed
+                Some(member_scope) if member_scope.internal && !scope.internal => push_error(syn::Error::new(
+                    member.span(),
+                    "Internal scopes cannot be members of a group that can be parsed",
+                )),
+                Some(_) => (),
+            }
+        }
+    }
+
+    // Only look for loops between valid groups
+    if let Some(err) = error {
+        return Err(err);
+    }
+
+    let mut path: Vec<&Ident> = Vec::new();
+    for scope in scopes {
+        if let Some(cycle) = find_cycle(scopes, &scope.ident, &mut path) {
+            let cycle = cycle.iter().map(|ident| ident.to_string()).collect::<Vec<_>>().join(" -> ");
+            let err = syn::Error::new(scope.ident.span(), format!("Group expands to itself: {}", cycle));
+            match error.as_mut() {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
+            }
+        }
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Look for a path of groups going from `ident` back to the start of `path`
+fn find_cycle<'a>(scopes: &'a [Scope], ident: &'a Ident, path: &mut Vec<&'a Ident>) -> Option<Vec<&'a Ident>> {
+
+    if path.first() == Some(&ident) {
+        let mut cycle = path.clone();
+        cycle.push(ident);
+        return Some(cycle);
+    }
+
+    // Loops not going through the start of the path are reported for their own groups
+    if path.contains(&ident) {
+        return None;
+    }
+
+    let scope = scopes.iter().find(|s| &s.ident == ident)?;
+    path.push(ident);
+
+    let cycle = scope.expands_to.iter().find_map(|member| find_cycle(scopes, member, path));
+
+    path.pop();
+    cycle
+}
+
+// Get the concrete scopes a group expands to, replacing nested groups by their members
+fn flatten<'a>(scopes: &'a [Scope], scope: &'a Scope, members: &mut Vec<&'a Ident>) {
+    for member in &scope.expands_to {
+        let member_scope = scopes.iter().find(|s| &s.ident == member).expect("Groups should be validated");
+
+        if member_scope.expands_to.is_empty() {
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        } else {
+            flatten(scopes, member_scope, members);
+        }
+    }
+}
+
+// Implement the Scope::expansion() function, or nothing if there are no groups or nested scopes
+pub fn implement_expansion(enum_ident: &Ident, scopes: &[Scope]) -> TokenStream {
+
+    let groups: Vec<&Scope> = scopes.iter().filter(|s| !s.expands_to.is_empty()).collect();
+    let nested: Vec<&Scope> = scopes.iter().filter(|s| s.nested.is_some()).collect();
+
+    if groups.is_empty() && nested.is_empty() {
+        return quote! {};
+    }
+
+    // Groups of the nested scope enums are wrapped in the nested variant
+    let nested_expansions = nested.iter().map(|scope| {
+        let pattern = scope.fields.pattern(enum_ident, &scope.ident, &["0"], CONSTRUCTOR_PREFIX);
+        let binding = param_binding(CONSTRUCTOR_PREFIX, "0");
+        let variant = &scope.ident;
+        quote! {
+            #pattern => ::scopes_rs::scope::Scope::expansion(#binding)
+                .map(|members| members.into_iter().map(#enum_ident::#variant).collect()),
+        }
+    });
+
+    let patterns = groups.iter().map(|scope| scope.fields.pattern(enum_ident, &scope.ident, &[], CONSTRUCTOR_PREFIX));
+    let members = groups.iter().map(|scope| {
+        let mut members = Vec::new();
+        flatten(scopes, scope, &mut members);
+        quote! { ::std::vec![#(#enum_ident::#members),*] }
+    });
+
+    quote! {
+        fn expansion(&self) -> ::std::option::Option<::std::vec::Vec<Self>> {
+            match self {
+                #(#patterns => ::std::option::Option::Some(#members),)*
+                #(#nested_expansions)*
+                _ => ::std::option::Option::None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::scope::{Scope, expand::validate_groups};
+
+    fn scope(ident: syn::Ident, expands_to: Vec<syn::Ident>) -> Scope {
+        let mut scope = Scope::_test_new_full(ident.clone(), [ident.to_string()], ".", "", Vec::new());
+        scope.expands_to = expands_to;
+        scope
+    }
+
+    #[test]
+    fn test_validate_groups() {
+        let enum_ident: syn::Ident = parse_quote!(MyScope);
+
+        let valid = vec![
+            scope(parse_quote!(Foo), vec![]),
+            scope(parse_quote!(Bar), vec![parse_quote!(Foo)]),
+            scope(parse_quote!(Baz), vec![parse_quote!(Foo), parse_quote!(Bar)]),
+        ];
+        assert!(validate_groups(&enum_ident, &valid).is_ok());
+
+        let unknown = vec![scope(parse_quote!(Foo), vec![parse_quote!(Bar)])];
+        assert!(validate_groups(&enum_ident, &unknown).is_err());
+
+        let cycle = vec![
+            scope(parse_quote!(Foo), vec![parse_quote!(Bar)]),
+            scope(parse_quote!(Bar), vec![parse_quote!(Baz)]),
+            scope(parse_quote!(Baz), vec![parse_quote!(Foo)]),
+        ];
+        let err = validate_groups(&enum_ident, &cycle).unwrap_err();
+        assert_eq!(err.to_string(), "Group expands to itself: Foo -> Bar -> Baz -> Foo");
+
+        let self_expansion = vec![scope(parse_quote!(Foo), vec![parse_quote!(Foo)])];
+        assert!(validate_groups(&enum_ident, &self_expansion).is_err());
+
+        // Internal scopes can only be members of internal groups
+        let mut internal = vec![
+            scope(parse_quote!(Foo), vec![]),
+            scope(parse_quote!(Bar), vec![parse_quote!(Foo)]),
+        ];
+        internal[0].internal = true;
+        let err = validate_groups(&enum_ident, &internal).unwrap_err();
+        assert_eq!(err.to_string(), "Internal scopes cannot be members of a group that can be parsed");
+
+        internal[1].internal = true;
+        assert!(validate_groups(&enum_ident, &internal).is_ok());
+    }
+}
@@ -1,5 +1,9 @@
+#[allow(clippy::module_inception)]
 mod scope;
 pub mod scope_impl;
 pub mod opts;
+pub mod template;
+pub mod request;
+pub mod expand;
 
 pub use scope::*;
@@ -1,16 +1,27 @@
 #[cfg(feature = "hierarchy")]
-use darling::FromMeta;
-use darling::{FromDeriveInput, FromVariant, ast};
+use std::collections::HashMap;
+
+use darling::{FromDeriveInput, FromMeta, FromVariant, ast};
+
+use crate::scope::template::ScopeFieldOpts;
 
 // Options for the enum to be derived
 #[derive(FromDeriveInput)]
-#[darling(attributes(scope), supports(enum_unit))]
+#[darling(attributes(scope), supports(enum_any), and_then = ScopeOpts::validate)]
 pub struct ScopeOpts {
     pub ident: syn::Ident,
     pub vis: syn::Visibility,
 
-    #[darling(default = || ".".to_string())]
-    pub separator: String,
+    // Separator between labels, defaults to the separator of the naming convention
+    pub separator: Option<String>,
+
+    // Naming convention for the inferred scope names
+    #[darling(default)]
+    pub rename_all: RenameAll,
+
+    // How variant names are split in labels
+    #[darling(default)]
+    pub split: SplitMode,
 
     #[darling(default)]
     pub prefix: String,
@@ -19,23 +30,218 @@ pub struct ScopeOpts {
     #[darling(default = || true)]
     pub hierarchy: bool,
 
+    // Allow inclusion cycles, making the scopes of a cycle equivalent
+    #[cfg(feature = "hierarchy")]
+    #[darling(default)]
+    pub allow_cycles: bool,
+
+    // Include scopes in the scopes whose labels are a prefix of their labels
+    #[cfg(feature = "hierarchy")]
+    #[darling(default = || true)]
+    pub infer_hierarchy: bool,
+
+    // Store the inclusions in a table indexed by the variant indices
+    #[cfg(feature = "hierarchy")]
+    #[darling(default)]
+    pub inclusion_table: bool,
+
     // Add a function to get the scope name
     #[darling(default = || false)]
     pub scope_name_getter: bool,
 
+    // Implement Display with the scope name
+    #[darling(default)]
+    pub display: bool,
+
+    // Ignore ASCII case and surrounding whitespace when parsing
+    #[darling(default)]
+    pub case_insensitive: bool,
+
+    // Function called on the input before parsing
+    pub normalize: Option<syn::Path>,
+
+    // Recognize wildcard grants like `foo.*`
+    #[darling(default)]
+    pub wildcard: bool,
+
+    // Check that scope names are valid OAuth 2.0 scope tokens
+    #[darling(default = || true)]
+    pub validate_names: bool,
+
     pub data: ast::Data<ScopeVariantOpts, ()>,
 }
 
+impl ScopeOpts {
+
+    fn validate(self) -> darling::Result<Self> {
+        if self.separator.is_some() && self.rename_all != RenameAll::default() {
+            return Err(darling::Error::custom("`separator` cannot be used with `rename_all`").with_span(&self.ident));
+        }
+        if self.wildcard && self.separator().is_empty() {
+            return Err(darling::Error::custom("`wildcard` requires a non-empty separator").with_span(&self.ident));
+        }
+        Ok(self)
+    }
+
+    // Get the separator between labels
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(self.rename_all.separator())
+    }
+
+    // Get the string matched against the parsed input for a scope name
+    pub fn parse_key(&self, name: &str) -> String {
+        if self.case_insensitive {
+            name.to_ascii_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+// Naming convention used to infer the scope names from the variant names
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum RenameAll {
+    // foo.bar
+    #[default]
+    DotCase,
+    // foo_bar
+    SnakeCase,
+    // foo-bar
+    KebabCase,
+    // foo:bar
+    ColonCase,
+    // FOO_BAR
+    ScreamingSnakeCase,
+    // FooBar
+    Verbatim,
+}
+
+impl RenameAll {
+
+    // Get the separator between the labels
+    pub fn separator(&self) -> &'static str {
+        match self {
+            RenameAll::DotCase => ".",
+            RenameAll::SnakeCase | RenameAll::ScreamingSnakeCase => "_",
+            RenameAll::KebabCase => "-",
+            RenameAll::ColonCase => ":",
+            RenameAll::Verbatim => "",
+        }
+    }
+
+    // Convert a word of the variant name to a label
+    pub fn convert_label(&self, word: &str) -> String {
+        match self {
+            RenameAll::ScreamingSnakeCase => word.to_uppercase(),
+            RenameAll::Verbatim => word.to_string(),
+            _ => word.to_lowercase(),
+        }
+    }
+}
+
+impl FromMeta for RenameAll {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "dot.case" => Ok(RenameAll::DotCase),
+            "snake_case" => Ok(RenameAll::SnakeCase),
+            "kebab-case" => Ok(RenameAll::KebabCase),
+            "colon:case" => Ok(RenameAll::ColonCase),
+            "SCREAMING_SNAKE" | "SCREAMING_SNAKE_CASE" => Ok(RenameAll::ScreamingSnakeCase),
+            "verbatim" => Ok(RenameAll::Verbatim),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+// How variant names are split in words
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SplitMode {
+    // Split at each uppercase letter: HTTPRequest -> h.t.t.p.request
+    #[default]
+    Uppercase,
+    // Keep acronyms and digits together: HTTPRequest -> http.request
+    Acronyms,
+}
+
+impl FromMeta for SplitMode {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "uppercase" => Ok(SplitMode::Uppercase),
+            "acronyms" => Ok(SplitMode::Acronyms),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
 // Options for an enum variant of the scope enum
 #[derive(Debug, FromVariant)]
-#[darling(attributes(scope))]
+#[darling(attributes(scope), forward_attrs(doc))]
 pub struct ScopeVariantOpts {
     pub ident: syn::Ident,
 
+    pub fields: ast::Fields<ScopeFieldOpts>,
+
+    // Doc comments, used as a fallback for the description
+    pub attrs: Vec<syn::Attribute>,
+
     pub rename: Option<String>,
 
+    // Alternative names accepted when parsing
+    #[darling(multiple, rename = "alias")]
+    pub aliases: Vec<String>,
+
+    // Scope metadata
+    pub description: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub icon: Option<String>,
+
+    // Catch-all variant for unknown scopes
+    #[darling(default)]
+    pub other: bool,
+
+    // Variant wrapping another scope enum
+    #[darling(default)]
+    pub nested: bool,
+
+    // Internal variant that can't be parsed, `skip_parse` is an alias of `internal`
+    #[darling(default)]
+    pub internal: bool,
+    #[darling(default)]
+    pub skip_parse: bool,
+
+    // Scope granted when no scope is requested
+    #[darling(default)]
+    pub default: bool,
+
+    // Scopes that must be requested with this one
+    pub requires: Option<VariantList>,
+
+    // Scopes that can't be requested with this one
+    pub conflicts_with: Option<VariantList>,
+
+    // Scopes replacing this one when parsing a list of scopes
+    pub expands_to: Option<VariantList>,
+
+    #[cfg(feature = "hierarchy")]
+    pub include: Option<VariantList>,
+
+    // Scopes not included in this one by their labels
+    #[cfg(feature = "hierarchy")]
+    pub exclude: Option<VariantList>,
+
+    // Scopes including this one
     #[cfg(feature = "hierarchy")]
-    pub include: Option<IncludeList>,
+    pub parent: Option<VariantList>,
+
+    // Scopes included in the given variants of the nested scope enum
+    #[cfg(feature = "hierarchy")]
+    pub child_include: Option<HashMap<syn::Ident, VariantList>>,
+
+    // Allow `include` and `parent` to leave parameters of the included scope unbound
+    #[cfg(feature = "hierarchy")]
+    #[darling(default)]
+    pub allow_unbound_params: bool,
 }
 
 #[cfg(test)]
@@ -43,19 +249,41 @@ impl Default for ScopeVariantOpts {
     fn default() -> Self {
         Self { 
             ident: syn::parse_quote!(Foo), 
+            fields: ast::Fields::new(ast::Style::Unit, Vec::new()),
+            attrs: Default::default(),
             rename: Default::default(),
+            aliases: Default::default(),
+            description: Default::default(),
+            title: Default::default(),
+            category: Default::default(),
+            icon: Default::default(),
+            other: Default::default(),
+            nested: Default::default(),
+            internal: Default::default(),
+            skip_parse: Default::default(),
+            default: Default::default(),
+            requires: Default::default(),
+            conflicts_with: Default::default(),
+            expands_to: Default::default(),
+            #[cfg(feature = "hierarchy")]
+            include: Default::default(),
             #[cfg(feature = "hierarchy")]
-            include: Default::default()
+            exclude: Default::default(),
+            #[cfg(feature = "hierarchy")]
+            parent: Default::default(),
+            #[cfg(feature = "hierarchy")]
+            child_include: Default::default(),
+            #[cfg(feature = "hierarchy")]
+            allow_unbound_params: Default::default(),
         }
     }
 }
 
-#[cfg(feature = "hierarchy")]
+// A list of enum variants, like `Variant`, `MyEnum::Variant` or `[VariantA, VariantB]`
 #[derive(Debug)]
-pub struct IncludeList(pub Vec<syn::Ident>);
+pub struct VariantList(pub Vec<syn::Ident>);
 
-#[cfg(feature = "hierarchy")]
-impl FromMeta for IncludeList {
+impl FromMeta for VariantList {
 
     fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
 
@@ -71,32 +299,22 @@ impl FromMeta for IncludeList {
             },
 
             // Single scope
-            syn::Expr::Path(_) => Ok(IncludeList(vec![parse_included_scope(expr)?])),
+            syn::Expr::Path(_) => Ok(VariantList(vec![parse_variant_ident(expr)?])),
 
             // List of included scopes
             syn::Expr::Array(syn::ExprArray { ref elems, ..})
             | syn::Expr::Tuple(syn::ExprTuple { ref elems, .. }) => {
                 
-                // Parse each expression in the list
+                // Parse each expression in the list, combining errors
+                let mut errors = darling::Error::accumulator();
+
                 let parsed_elems: Vec<syn::Ident> = elems.iter()
-                    .map(parse_included_scope)
-
-                    // Fold into a vec or combine errors
-                    .fold(Ok(Vec::new()), |acc, r| match (acc, r) {
-                        (Ok(mut elts), Ok(e)) => {
-                            elts.push(e);
-                            Ok(elts)
-                        },
-                        (Ok(_), Err(e)) => Err(vec![e]),
-                        (Err(acc), Ok(_)) => Err(acc),
-                        (Err(mut acc), Err(e)) => {
-                            acc.push(e);
-                            Err(acc)
-                        },
-                    })
-                    .map_err(darling::Error::multiple)?;
-
-                Ok(IncludeList(parsed_elems))
+                    .filter_map(|elem| errors.handle(parse_variant_ident(elem)))
+                    .collect();
+
+                errors.finish()?;
+
+                Ok(VariantList(parsed_elems))
             },
 
             _ => Err(darling::Error::unexpected_expr_type(expr)),
@@ -105,8 +323,7 @@ impl FromMeta for IncludeList {
     }
 }
 
-#[cfg(feature = "hierarchy")]
-fn parse_included_scope(expr: &syn::Expr) -> Result<syn::Ident, darling::Error> {
+fn parse_variant_ident(expr: &syn::Expr) -> Result<syn::Ident, darling::Error> {
     use syn::spanned::Spanned;
 
     
@@ -126,74 +343,94 @@ fn parse_included_scope(expr: &syn::Expr) -> Result<syn::Ident, darling::Error>
 
 #[cfg(test)]
 mod tests {
-    #[cfg(feature = "hierarchy")]
-    use darling::FromMeta;
-    #[cfg(feature = "hierarchy")]
+    use darling::{FromDeriveInput, FromMeta};
     use syn::parse_quote;
 
-    #[cfg(feature = "hierarchy")]
-    use crate::scope::opts::{parse_included_scope, IncludeList};
+    use crate::scope::opts::{RenameAll, ScopeOpts};
+
+
+    use crate::scope::opts::{parse_variant_ident, VariantList};
 
-    #[cfg(feature = "hierarchy")]
     macro_rules! ident {
         ($i: ident) => {
             {let ident: syn::Ident = parse_quote!($i); ident}
         };
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
-    fn test_parse_included_scope_variant_only() {
+    fn test_parse_variant_ident_variant_only() {
         let expr: syn::Expr = parse_quote! {Foo};
 
         let expected: syn::Ident = parse_quote!(Foo);
 
-        assert_eq!(parse_included_scope(&expr).unwrap(), expected);
+        assert_eq!(parse_variant_ident(&expr).unwrap(), expected);
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
-    fn test_parse_included_scope_variant_full() {
+    fn test_parse_variant_ident_variant_full() {
         let expr: syn::Expr = parse_quote! {MyScope::Foo};
 
         let expected: syn::Ident = parse_quote!(Foo);
 
-        assert_eq!(parse_included_scope(&expr).unwrap(), expected);
+        assert_eq!(parse_variant_ident(&expr).unwrap(), expected);
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
-    fn test_parse_included_scope_path_too_long() {
+    fn test_parse_variant_ident_path_too_long() {
         let expr: syn::Expr = parse_quote! {crate::MyScope::Foo};
 
-        assert!(parse_included_scope(&expr).is_err());
+        assert!(parse_variant_ident(&expr).is_err());
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
-    fn test_parse_included_scope_invalid_type() {
+    fn test_parse_variant_ident_invalid_type() {
 
-        assert!(parse_included_scope(&parse_quote! {"foo"}).is_err());
-        assert!(parse_included_scope(&parse_quote! {[Foo,]}).is_err());
-        assert!(parse_included_scope(&parse_quote! {(Foo,)}).is_err());
+        assert!(parse_variant_ident(&parse_quote! {"foo"}).is_err());
+        assert!(parse_variant_ident(&parse_quote! {[Foo,]}).is_err());
+        assert!(parse_variant_ident(&parse_quote! {(Foo,)}).is_err());
 
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
     fn test_parse_include_single_scope() {
-        assert_eq!(IncludeList::from_expr(&parse_quote! {MyScope::Foo}).unwrap().0, vec![ident!(Foo)]);
-        assert_eq!(IncludeList::from_expr(&parse_quote! {Bar}).unwrap().0, vec![ident!(Bar)]);
+        assert_eq!(VariantList::from_expr(&parse_quote! {MyScope::Foo}).unwrap().0, vec![ident!(Foo)]);
+        assert_eq!(VariantList::from_expr(&parse_quote! {Bar}).unwrap().0, vec![ident!(Bar)]);
     }
 
-    #[cfg(feature = "hierarchy")]
     #[test]
     fn test_parse_include_multiple_scopes() {
         
-        let parsed_array = IncludeList::from_expr(&parse_quote! {[Foo, MyScope::Bar, Baz]}).unwrap().0;
-        let parsed_tuple = IncludeList::from_expr(&parse_quote! {(Foo, MyScope::Bar, Baz)}).unwrap().0;
+        let parsed_array = VariantList::from_expr(&parse_quote! {[Foo, MyScope::Bar, Baz]}).unwrap().0;
+        let parsed_tuple = VariantList::from_expr(&parse_quote! {(Foo, MyScope::Bar, Baz)}).unwrap().0;
 
         assert_eq!(parsed_array, vec![ident!(Foo), ident!(Bar), ident!(Baz)]);
         assert_eq!(parsed_array, parsed_tuple);
     }
+
+    #[test]
+    fn test_parse_rename_all() {
+        assert_eq!(RenameAll::SnakeCase, RenameAll::from_string("snake_case").unwrap());
+        assert_eq!(RenameAll::ScreamingSnakeCase, RenameAll::from_string("SCREAMING_SNAKE").unwrap());
+        assert!(RenameAll::from_string("camelCase").is_err());
+    }
+
+    #[test]
+    fn test_separator() {
+        let opts = ScopeOpts::from_derive_input(&parse_quote! {
+            #[scope(rename_all = "kebab-case")]
+            enum MyScope { Foo }
+        }).unwrap();
+        assert_eq!("-", opts.separator());
+
+        let opts = ScopeOpts::from_derive_input(&parse_quote! {
+            #[scope(separator = "/")]
+            enum MyScope { Foo }
+        }).unwrap();
+        assert_eq!("/", opts.separator());
+
+        assert!(ScopeOpts::from_derive_input(&parse_quote! {
+            #[scope(separator = "/", rename_all = "kebab-case")]
+            enum MyScope { Foo }
+        }).is_err());
+    }
 }
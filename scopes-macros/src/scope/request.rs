@@ -0,0 +1,159 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::{Scope, diagnostic::unknown_variant, scope::template::CONSTRUCTOR_PREFIX};
+
+// Check that the targets of `requires` and `conflicts_with` are valid variants
+pub fn validate_targets(enum_ident: &Ident, scopes: &[Scope]) -> Result<(), syn::Error> {
+
+    let mut error: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match error.as_mut() {
+        Some(error) => error.combine(err),
+        None => error = Some(err),
+    };
+
+    let find = |ident: &Ident| scopes.iter().find(|s| &s.ident == ident);
+
+    for scope in scopes {
+
+        let targets = scope.requires.iter().map(|t| ("requires", t))
+            .chain(scope.conflicts_with.iter().map(|t| ("conflicts_with", t)));
+
+        for (attribute, target) in targets {
+            match find(target) {
+                None => push_error(unknown_variant(enum_ident, target, attribute, scopes.iter().map(|s| &s.ident))),
+                Some(target_scope) if target_scope.other => push_error(syn::Error::new(
+                    target.span(),
+                    format!("The `other` variant cannot be used in `{}`", attribute),
+                )),
+                Some(target_scope) if attribute == "requires" && !target_scope.fields.is_unit() => push_error(syn::Error::new(
+                    target.span(),
+                    "Required scopes cannot have fields",
+                )),
+                Some(target_scope) if attribute == "conflicts_with" && target_scope == scope => push_error(syn::Error::new(
+                    target.span(),
+                    "A scope cannot conflict with itself",
+                )),
+                Some(_) => (),
+            }
+        }
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Implement the validate_request() function
+pub fn implement_validate_request(enum_ident: &Ident, vis: &syn::Visibility, scopes: &[Scope]) -> TokenStream {
+
+    let pattern = |ident: &Ident| {
+        let scope = scopes.iter().find(|s| &s.ident == ident).expect("Targets should be validated");
+        scope.fields.pattern(enum_ident, ident, &[], CONSTRUCTOR_PREFIX)
+    };
+
+    // A required scope is present if a requested scope includes it
+    #[cfg(feature = "hierarchy")]
+    let satisfies = quote! { ::scopes_rs::hierarchy::Hierarchized::includes(s, &required) };
+
+    #[cfg(not(feature = "hierarchy"))]
+    let satisfies = quote! { s == &required };
+
+    let requirements = scopes.iter().filter(|s| !s.requires.is_empty()).map(|scope| {
+        let scope_pattern = pattern(&scope.ident);
+        let required = &scope.requires;
+
+        quote! {
+            #scope_pattern => {
+                #(
+                    let required = #enum_ident::#required;
+                    if !scopes.iter().any(|s| !::scopes_rs::scope::Scope::is_catch_all(s) && #satisfies) {
+                        errors.push(::scopes_rs::error::ScopeRequestError::MissingRequirement { scope, required });
+                    }
+                )*
+            }
+        }
+    });
+
+    // Each pair of conflicting scopes is only checked once
+    let mut conflicts: Vec<(&Ident, &Ident)> = Vec::new();
+    for scope in scopes {
+        for other in &scope.conflicts_with {
+            if !conflicts.iter().any(|(a, b)| (*a == other && *b == &scope.ident) || (*a == &scope.ident && *b == other)) {
+                conflicts.push((&scope.ident, other));
+            }
+        }
+    }
+
+    let conflicts = conflicts.into_iter().map(|(a, b)| {
+        let pattern_a = pattern(a);
+        let pattern_b = pattern(b);
+
+        quote! {
+            if let (Some(scope), Some(conflicting)) = (
+                scopes.iter().find(|s| matches!(s, #pattern_a)),
+                scopes.iter().find(|s| matches!(s, #pattern_b)),
+            ) {
+                errors.push(::scopes_rs::error::ScopeRequestError::Conflict { scope, conflicting });
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_ident {
+            /// Check that a set of requested scopes contains the scopes required by each scope,
+            /// and no conflicting scopes.
+            #vis fn validate_request(scopes: &[Self]) -> ::std::result::Result<(), ::std::vec::Vec<::scopes_rs::error::ScopeRequestError<'_, Self>>> {
+                let mut errors = ::std::vec::Vec::new();
+
+                for scope in scopes {
+                    match scope {
+                        #(#requirements)*
+                        _ => (),
+                    }
+                }
+
+                #(#conflicts)*
+
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::scope::{Scope, request::validate_targets};
+
+    fn scope(ident: syn::Ident, requires: Vec<syn::Ident>, conflicts_with: Vec<syn::Ident>) -> Scope {
+        let mut scope = Scope::_test_new_full(ident.clone(), [ident.to_string()], ".", "", Vec::new());
+        scope.requires = requires;
+        scope.conflicts_with = conflicts_with;
+        scope
+    }
+
+    #[test]
+    fn test_validate_targets() {
+        let enum_ident: syn::Ident = parse_quote!(MyScope);
+
+        let valid = vec![
+            scope(parse_quote!(Foo), vec![parse_quote!(Bar)], vec![]),
+            scope(parse_quote!(Bar), vec![], vec![parse_quote!(Foo)]),
+        ];
+        assert!(validate_targets(&enum_ident, &valid).is_ok());
+
+        let unknown = vec![scope(parse_quote!(Foo), vec![parse_quote!(Baz)], vec![])];
+        assert!(validate_targets(&enum_ident, &unknown).is_err());
+
+        let self_conflict = vec![scope(parse_quote!(Foo), vec![], vec![parse_quote!(Foo)])];
+        assert!(validate_targets(&enum_ident, &self_conflict).is_err());
+    }
+}
@@ -2,7 +2,10 @@ use std::hash::Hash;
 
 use syn::Ident;
 
-use crate::scope::opts::{ScopeOpts, ScopeVariantOpts};
+use crate::scope::{opts::{RenameAll, ScopeOpts, ScopeVariantOpts, SplitMode}, template::{VariantFields, param_name}};
+
+// Label standing for the name of the wrapped scope in the labels of a nested scope
+pub const NESTED_LABEL: &str = "{0}";
 
 // TODO: Implementation without cloning separator and prefix if feasible
 #[cfg_attr(test,derive(Debug))]
@@ -11,17 +14,37 @@ pub struct Scope {
     // Ident of the corresponding enum variant
     pub ident: Ident,
 
+    // Fields of the enum variant, used as parameters in the name
+    pub fields: VariantFields,
+
+    // Whether the name was given with the `rename` attribute
+    pub renamed: bool,
+
+    // Whether this is the catch-all variant for unknown scopes
+    pub other: bool,
+
+    // Type of the scope enum wrapped by this variant, if it is a nested scope
+    pub nested: Option<syn::Type>,
+
+    // Whether this scope can't be parsed from a string
+    pub internal: bool,
+
+    // Whether this scope is granted by default
+    pub default: bool,
+
+    // Scopes that must be requested with this one
+    pub requires: Vec<Ident>,
+
+    // Scopes that can't be requested with this one
+    pub conflicts_with: Vec<Ident>,
+
+    // Scopes replacing this one when parsing a list of scopes
+    pub expands_to: Vec<Ident>,
+
     // List of labels comprising the scope, used to determine hierarchy
-    #[cfg(feature = "hierarchy")]
     pub labels: Vec<String>,
 
-    // We dont need to split labels without hierarchy
-    #[cfg(not(feature = "hierarchy"))]
-    pub scope_name: String,
-
     // Separator for the labels
-    // Only useful if hierarchy is enabled to reconstruct the full name
-    #[cfg(feature = "hierarchy")]
     separator: String,
 
     // Name prefix
@@ -30,6 +53,46 @@ pub struct Scope {
     // List of additional scopes to include
     #[cfg(feature = "hierarchy")]
     pub include: Vec<syn::Ident>,
+
+    // List of scopes not included by their labels
+    #[cfg(feature = "hierarchy")]
+    pub exclude: Vec<syn::Ident>,
+
+    // List of scopes including this one
+    #[cfg(feature = "hierarchy")]
+    pub parents: Vec<syn::Ident>,
+
+    // Scopes included in variants of the nested scope enum, sorted by variant
+    #[cfg(feature = "hierarchy")]
+    pub child_include: Vec<(syn::Ident, Vec<syn::Ident>)>,
+
+    // Include the scopes whose labels start with the labels of this scope
+    #[cfg(feature = "hierarchy")]
+    pub label_inclusions: bool,
+
+    // Allow the `include` and `parent` attributes of this scope to leave parameters unbound
+    #[cfg(feature = "hierarchy")]
+    pub allow_unbound_params: bool,
+
+    // Type of the field of each parameter
+    #[cfg(feature = "hierarchy")]
+    pub param_types: Vec<(String, syn::Type)>,
+
+    // Alternative names accepted when parsing
+    pub aliases: Vec<String>,
+
+    // Human-readable metadata
+    pub metadata: Metadata,
+}
+
+// Human-readable metadata of a scope
+#[derive(Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Metadata {
+    pub description: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub icon: Option<String>,
 }
 
 impl PartialEq for Scope {
@@ -44,83 +107,225 @@ impl Hash for Scope {
     }
 }
 
-// Extract a list of labels from an enum variant ident.
+// Split an enum variant ident in words.
 // This splits the name of the variant at each capitalized letter
-fn get_labels_from_ident(ident: &Ident) -> Vec<String> {
+fn get_words_from_ident(ident: &Ident) -> Vec<String> {
 
-    let mut labels = Vec::new();
-    let mut current_label = String::new();
+    let mut words = Vec::new();
+    let mut current_word = String::new();
 
     for (i, ch) in ident.to_string().char_indices() {
 
         if i > 0 && ch.is_uppercase() {
-            labels.push(current_label);
-            current_label = String::new();
+            words.push(current_word);
+            current_word = String::new();
+        }
+
+        current_word.push(ch);
+    }
+    words.push(current_word);
+
+    words
+}
+
+// Split an enum variant ident in words, keeping acronyms and digits together.
+// A new word starts at an uppercase letter following a lowercase letter or a digit,
+// or at the last uppercase letter of an acronym followed by a lowercase letter.
+fn get_words_from_ident_acronyms(ident: &Ident) -> Vec<String> {
+
+    let chars: Vec<char> = ident.to_string().chars().collect();
+
+    let mut words = Vec::new();
+    let mut current_word = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+
+        if i > 0 && ch.is_uppercase() {
+            let previous = chars[i - 1];
+            let next = chars.get(i + 1);
+
+            let after_word = previous.is_lowercase() || previous.is_ascii_digit();
+            let ends_acronym = previous.is_uppercase() && next.is_some_and(|c| c.is_lowercase());
+
+            if after_word || ends_acronym {
+                words.push(current_word);
+                current_word = String::new();
+            }
         }
 
-        current_label.push(ch.to_ascii_lowercase());
+        current_word.push(ch);
     }
-    labels.push(current_label);
+    words.push(current_word);
 
-    return labels;
+    words
+}
+
+// Extract a list of labels from an enum variant ident, following a naming convention
+fn get_labels_from_ident(ident: &Ident, rename_all: RenameAll, split: SplitMode) -> Vec<String> {
+
+    let words = match split {
+        SplitMode::Uppercase => get_words_from_ident(ident),
+        SplitMode::Acronyms => get_words_from_ident_acronyms(ident),
+    };
+
+    words.iter()
+        .map(|word| rename_all.convert_label(word))
+        .collect()
+}
+
+// Extract a description from the doc comments of an enum variant.
+// Each line is trimmed, and the lines are joined with a newline.
+fn get_description_from_doc(attrs: &[syn::Attribute]) -> Option<String> {
+
+    let lines: Vec<String> = attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. }),
+                ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let description = lines.join("\n").trim().to_string();
+
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
 }
 
 
 impl Scope {
 
     pub fn from_variant(variant_opts: &ScopeVariantOpts, opts: &ScopeOpts) -> Self {
-        
+
+        let metadata = Metadata {
+            description: variant_opts.description.clone().or_else(|| get_description_from_doc(&variant_opts.attrs)),
+            title: variant_opts.title.clone(),
+            category: variant_opts.category.clone(),
+            icon: variant_opts.icon.clone(),
+        };
+
+        let separator = opts.separator();
+
         let labels = match &variant_opts.rename {
 
-            // If hierarchy is not enabled, we don't need to extract the labels from the name
-            #[cfg(not(feature = "hierarchy"))]
-            Some(name) => {
-                return Self {
-                    ident: variant_opts.ident.clone(),
-                    scope_name: name.clone(),
-                    prefix: opts.prefix.clone(),
-                };
-            },
-
-            // If hierarchy is enabled, extract the labels from the given name
-            #[cfg(feature="hierarchy")]
-            Some(name) => name.split(&opts.separator).map(String::from).collect(),
-
-            None => get_labels_from_ident(&variant_opts.ident),
+            // A name can't be split with an empty separator
+            Some(name) if separator.is_empty() => vec![name.clone()],
+
+            // Extract the labels from the given name
+            Some(name) => name.split(separator).map(String::from).collect(),
+
+            None => get_labels_from_ident(&variant_opts.ident, opts.rename_all, opts.split),
+        };
+
+        // The name of a nested scope is the name of the variant, followed by the name of the wrapped scope
+        let (labels, nested) = if variant_opts.nested {
+            let ty = variant_opts.fields.iter().next().map(|f| f.ty.clone());
+            ([labels, vec![NESTED_LABEL.to_string()]].concat(), Some(ty.unwrap_or(syn::Type::Verbatim(Default::default()))))
+        } else {
+            (labels, None)
+        };
+
+        #[cfg(feature = "hierarchy")]
+        let child_include = {
+            let mut child_include: Vec<(syn::Ident, Vec<syn::Ident>)> = variant_opts.child_include.iter()
+                .flatten()
+                .map(|(child, included)| (child.clone(), included.0.clone()))
+                .collect();
+            child_include.sort_by_key(|(child, _)| child.to_string());
+            child_include
         };
 
-        return Self {
+        Self {
             ident: variant_opts.ident.clone(),
+            fields: VariantFields::from_fields(&variant_opts.fields),
+            renamed: variant_opts.rename.is_some(),
+            other: variant_opts.other,
+            nested,
+            internal: variant_opts.internal || variant_opts.skip_parse,
+            default: variant_opts.default,
+            requires: variant_opts.requires.as_ref().map(|r| r.0.clone()).unwrap_or_default(),
+            conflicts_with: variant_opts.conflicts_with.as_ref().map(|c| c.0.clone()).unwrap_or_default(),
+            expands_to: variant_opts.expands_to.as_ref().map(|e| e.0.clone()).unwrap_or_default(),
             prefix: opts.prefix.clone(),
-            
+            separator: separator.to_string(),
+            labels,
+
             #[cfg(feature = "hierarchy")]
-            separator: opts.separator.clone(),
-            
+            include: variant_opts.include.as_ref().map(|i| i.0.clone()).unwrap_or_default(),
+
             #[cfg(feature = "hierarchy")]
-            labels,
+            exclude: variant_opts.exclude.as_ref().map(|e| e.0.clone()).unwrap_or_default(),
 
             #[cfg(feature = "hierarchy")]
-            include: variant_opts.include.as_ref().map(|i| i.0.clone()).unwrap_or(Vec::new()),
-            
-            #[cfg(not(feature = "hierarchy"))]
-            scope_name: labels.join(&opts.separator),
+            parents: variant_opts.parent.as_ref().map(|p| p.0.clone()).unwrap_or_default(),
+
+            #[cfg(feature = "hierarchy")]
+            child_include,
+
+            #[cfg(feature = "hierarchy")]
+            label_inclusions: opts.infer_hierarchy,
+
+            #[cfg(feature = "hierarchy")]
+            allow_unbound_params: variant_opts.allow_unbound_params,
+
+            #[cfg(feature = "hierarchy")]
+            param_types: VariantFields::from_fields(&variant_opts.fields).param_names().into_iter()
+                .zip(variant_opts.fields.iter().map(|f| f.ty.clone()))
+                .collect(),
+
+            aliases: variant_opts.aliases.clone(),
+            metadata,
         }
     }
 
     pub fn name(&self) -> String {
-        #[cfg(not(feature = "hierarchy"))]
-        return self.scope_name.clone();
-
-        #[cfg(feature = "hierarchy")]
-        return self.labels.join(&self.separator);
+        self.labels.join(&self.separator)
     }
 
     pub fn full_name(&self) -> String {
-        #[cfg(not(feature = "hierarchy"))]
-        return self.prefix.clone() + &self.scope_name;
+        self.prefix.clone() + &self.name()
+    }
 
-        #[cfg(feature = "hierarchy")]
-        return self.prefix.clone() + &self.labels.join(&self.separator);
+    pub fn is_parameterized(&self) -> bool {
+        !self.fields.is_unit()
+    }
+
+    // Get the scopes in the order of their index : the variants without fields in declaration order,
+    // so they have the same index as in `ALL`, followed by the variants with fields in declaration order
+    pub fn index_order(scopes: &[Scope]) -> Vec<&Scope> {
+        let (units, parameterized): (Vec<&Scope>, Vec<&Scope>) = scopes.iter().partition(|s| !s.is_parameterized());
+        units.into_iter().chain(parameterized).collect()
+    }
+
+    // Get the position and name of each parameter in the labels
+    pub fn params(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.labels.iter().enumerate().filter_map(|(i, l)| param_name(l).map(|p| (i, p)))
+    }
+
+    // Get the type of the field of a parameter
+    #[cfg(feature = "hierarchy")]
+    pub fn param_type(&self, param: &str) -> Option<&syn::Type> {
+        self.param_types.iter().find(|(name, _)| name == param).map(|(_, ty)| ty)
+    }
+
+    // Get the prefix of the scope name
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    // Get the separator between the labels
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    // Get the full name of each alias
+    pub fn full_aliases(&self) -> impl Iterator<Item = String> {
+        self.aliases.iter().map(|alias| self.prefix.clone() + alias)
     }
 
 }
@@ -133,50 +338,61 @@ mod tests {
     use darling::ast;
     use proc_macro2::Span;
 
-    use crate::scope::{Scope, opts::{ScopeOpts, ScopeVariantOpts}, scope::get_labels_from_ident};
+    use crate::scope::{Scope, opts::{RenameAll, ScopeOpts, ScopeVariantOpts, SplitMode}, scope::{get_description_from_doc, get_labels_from_ident, get_words_from_ident, get_words_from_ident_acronyms}};
+
+    use crate::scope::template::VariantFields;
 
     #[cfg(feature = "hierarchy")]
-    use crate::scope::opts::IncludeList;
+    use crate::scope::opts::VariantList;
 
     // Implement utility functions to create new scopes in tests
     impl Scope {
         #[cfg(feature = "hierarchy")]
         pub fn _test_new(ident: syn::Ident, labels: impl Iterator<Item = impl AsRef<str>>, separator: impl AsRef<str>, prefix: impl AsRef<str>) -> Self {
-            Self {
-                ident,
-                labels: labels.map(|s| String::from(s.as_ref())).collect(),
-                separator: separator.as_ref().to_owned(),
-                prefix: prefix.as_ref().to_owned(),
-                include: Vec::new(),
-            }
-        }
-
-        #[cfg(not(feature = "hierarchy"))]
-        pub fn _test_new(ident: syn::Ident, name: impl AsRef<str>, prefix: impl AsRef<str>) -> Self {
-            Self {
-                ident,
-                prefix: prefix.as_ref().to_owned(),
-                scope_name: name.as_ref().to_owned(),
-            }
+            Self::_test_new_full(ident, labels, separator, prefix, Vec::new())
         }
 
         // Create a scope struct corresponding to the enabled features
         #[allow(unused_variables)]
-        pub fn _test_new_full(ident: syn::Ident, name: impl AsRef<str>, labels: impl IntoIterator<Item = impl AsRef<str>>, separator: impl AsRef<str>, prefix: impl AsRef<str>, include: Vec<syn::Ident>) -> Self {
+        pub fn _test_new_full(ident: syn::Ident, labels: impl IntoIterator<Item = impl AsRef<str>>, separator: impl AsRef<str>, prefix: impl AsRef<str>, include: Vec<syn::Ident>) -> Self {
             Self {
                 ident,
+                fields: VariantFields::Unit,
+                renamed: false,
+                other: false,
+                nested: None,
+                internal: false,
+                default: false,
+                requires: Vec::new(),
+                conflicts_with: Vec::new(),
+                expands_to: Vec::new(),
                 prefix: prefix.as_ref().to_owned(),
+                labels: labels.into_iter().map(|s| String::from(s.as_ref())).collect(),
+                separator: separator.as_ref().to_owned(),
 
-                #[cfg(not(feature = "hierarchy"))]
-                scope_name: name.as_ref().to_owned(),
+                #[cfg(feature = "hierarchy")]
+                include,
 
                 #[cfg(feature = "hierarchy")]
-                labels: labels.into_iter().map(|s| String::from(s.as_ref())).collect(),
+                exclude: Vec::new(),
+
                 #[cfg(feature = "hierarchy")]
-                separator: separator.as_ref().to_owned(),
+                parents: Vec::new(),
 
                 #[cfg(feature = "hierarchy")]
-                include,
+                child_include: Vec::new(),
+
+                #[cfg(feature = "hierarchy")]
+                label_inclusions: true,
+
+                #[cfg(feature = "hierarchy")]
+                allow_unbound_params: false,
+
+                #[cfg(feature = "hierarchy")]
+                param_types: Vec::new(),
+
+                aliases: Vec::new(),
+                metadata: Default::default(),
             }
         }
     }
@@ -192,13 +408,29 @@ mod tests {
         ScopeOpts {
             ident: ident!(ScopeEnum),
             vis: syn::Visibility::Inherited,
-            separator: ".".to_string(),
+            separator: None,
+            rename_all: RenameAll::DotCase,
+            split: SplitMode::Uppercase,
             prefix: "".to_string(),
             
             #[cfg(feature = "hierarchy")]
             hierarchy: false,
 
+            #[cfg(feature = "hierarchy")]
+            allow_cycles: false,
+
+            #[cfg(feature = "hierarchy")]
+            infer_hierarchy: true,
+
+            #[cfg(feature = "hierarchy")]
+            inclusion_table: false,
+
             scope_name_getter: true,
+            display: true,
+            case_insensitive: false,
+            wildcard: false,
+            validate_names: true,
+            normalize: None,
 
             data: ast::Data::Enum(Vec::new()),
         }
@@ -206,15 +438,79 @@ mod tests {
 
     #[test]
     fn test_get_labels_simple() {
-        assert_eq!(vec!["foo"], get_labels_from_ident(&ident!(Foo)));
-        assert_eq!(vec!["foo", "bar"], get_labels_from_ident(&ident!(FooBar)));
-        assert_eq!(vec!["foo"], get_labels_from_ident(&ident!(foo)));
-        assert_eq!(vec!["foo_bar"], get_labels_from_ident(&ident!(foo_bar)));
+        assert_eq!(vec!["foo"], get_labels_from_ident(&ident!(Foo), RenameAll::DotCase, SplitMode::Uppercase));
+        assert_eq!(vec!["foo", "bar"], get_labels_from_ident(&ident!(FooBar), RenameAll::DotCase, SplitMode::Uppercase));
+        assert_eq!(vec!["foo"], get_labels_from_ident(&ident!(foo), RenameAll::DotCase, SplitMode::Uppercase));
+        assert_eq!(vec!["foo_bar"], get_labels_from_ident(&ident!(foo_bar), RenameAll::DotCase, SplitMode::Uppercase));
+    }
+
+    #[test]
+    fn test_get_words() {
+        assert_eq!(vec!["Foo", "Bar"], get_words_from_ident(&ident!(FooBar)));
+        assert_eq!(vec!["foo_bar"], get_words_from_ident(&ident!(foo_bar)));
+        assert_eq!(vec!["Scope_with", "Weird", "Case"], get_words_from_ident(&ident!(Scope_withWeirdCase)));
+    }
+
+    #[test]
+    fn test_get_words_acronyms() {
+        assert_eq!(vec!["Foo", "Bar"], get_words_from_ident_acronyms(&ident!(FooBar)));
+        assert_eq!(vec!["HTTP", "Request"], get_words_from_ident_acronyms(&ident!(HTTPRequest)));
+        assert_eq!(vec!["Http", "V2", "Access"], get_words_from_ident_acronyms(&ident!(HttpV2Access)));
+        assert_eq!(vec!["BAZ"], get_words_from_ident_acronyms(&ident!(BAZ)));
+        assert_eq!(vec!["Oauth2", "Token"], get_words_from_ident_acronyms(&ident!(Oauth2Token)));
+        assert_eq!(vec!["Read", "API"], get_words_from_ident_acronyms(&ident!(ReadAPI)));
+        assert_eq!(vec!["foo_bar"], get_words_from_ident_acronyms(&ident!(foo_bar)));
+    }
+
+    #[test]
+    fn test_get_labels_acronyms() {
+        assert_eq!(vec!["http", "request"], get_labels_from_ident(&ident!(HTTPRequest), RenameAll::DotCase, SplitMode::Acronyms));
+        assert_eq!(vec!["http", "v2", "access"], get_labels_from_ident(&ident!(HttpV2Access), RenameAll::DotCase, SplitMode::Acronyms));
+        assert_eq!(vec!["h", "t", "t", "p", "request"], get_labels_from_ident(&ident!(HTTPRequest), RenameAll::DotCase, SplitMode::Uppercase));
+    }
+
+    #[test]
+    fn test_get_labels_rename_all() {
+        assert_eq!(vec!["foo", "bar"], get_labels_from_ident(&ident!(FooBar), RenameAll::SnakeCase, SplitMode::Uppercase));
+        assert_eq!(vec!["FOO", "BAR"], get_labels_from_ident(&ident!(FooBar), RenameAll::ScreamingSnakeCase, SplitMode::Uppercase));
+        assert_eq!(vec!["Foo", "Bar"], get_labels_from_ident(&ident!(FooBar), RenameAll::Verbatim, SplitMode::Uppercase));
+    }
+
+    #[test]
+    fn test_from_variant_rename_all() {
+        let mut opts = default_opts();
+
+        let variant_opts = ScopeVariantOpts { ident: ident!(FooBarBaz), ..Default::default() };
+        let renamed = ScopeVariantOpts { ident: ident!(FooBarBaz), rename: Some("foo-bar".to_string()), ..Default::default() };
+
+        let expected = [
+            (RenameAll::DotCase, "foo.bar.baz"),
+            (RenameAll::SnakeCase, "foo_bar_baz"),
+            (RenameAll::KebabCase, "foo-bar-baz"),
+            (RenameAll::ColonCase, "foo:bar:baz"),
+            (RenameAll::ScreamingSnakeCase, "FOO_BAR_BAZ"),
+            (RenameAll::Verbatim, "FooBarBaz"),
+        ];
+
+        for (rename_all, name) in expected {
+            opts.rename_all = rename_all;
+            let scope = Scope::from_variant(&variant_opts, &opts);
+
+            assert_eq!(name, scope.name());
+            assert_eq!(3, scope.labels.len());
+        }
+
+        // Renamed variants are split with the separator of the naming convention
+        opts.rename_all = RenameAll::KebabCase;
+        assert_eq!(vec!["foo", "bar"], Scope::from_variant(&renamed, &opts).labels);
+
+        opts.rename_all = RenameAll::Verbatim;
+        assert_eq!(vec!["foo-bar"], Scope::from_variant(&renamed, &opts).labels);
     }
 
     #[test]
     fn test_get_labels_consecutive_uppercase() {
-        assert_eq!(vec!["h", "e", "l", "l", "o"], get_labels_from_ident(&ident!(HELLO)));
+        assert_eq!(vec!["h", "e", "l", "l", "o"], get_labels_from_ident(&ident!(HELLO), RenameAll::DotCase, SplitMode::Uppercase));
     }
 
     #[test]
@@ -227,10 +523,11 @@ mod tests {
             rename: None,
             #[cfg(feature = "hierarchy")]
             include: None,
+            ..Default::default()
         };
         assert_eq!(
             Scope::from_variant(&variant_opts, &opts),
-            Scope::_test_new_full(ident!(Foo), "foo", get_labels_from_ident(&ident!(Foo)).iter(), &opts.separator, &opts.prefix, vec![])
+            Scope::_test_new_full(ident!(Foo), get_labels_from_ident(&ident!(Foo), opts.rename_all, opts.split).iter(), opts.separator(), &opts.prefix, vec![])
         );
 
 
@@ -239,10 +536,11 @@ mod tests {
             rename: None,
             #[cfg(feature = "hierarchy")]
             include: None,
+            ..Default::default()
         };
         assert_eq!(
             Scope::from_variant(&variant_opts, &opts),
-            Scope::_test_new_full(ident!(FooBar), "foo.bar", get_labels_from_ident(&ident!(FooBar)).iter(), &opts.separator, &opts.prefix, vec![])
+            Scope::_test_new_full(ident!(FooBar), get_labels_from_ident(&ident!(FooBar), opts.rename_all, opts.split).iter(), opts.separator(), &opts.prefix, vec![])
         );
     }
 
@@ -254,25 +552,64 @@ mod tests {
             ident: ident!(FooBar),
             rename: Some("baz".to_string()),
             #[cfg(feature = "hierarchy")]
-            include: Some(IncludeList(Vec::new())),
+            include: Some(VariantList(Vec::new())),
+            ..Default::default()
         };
         assert_eq!(
             Scope::from_variant(&variant_opts, &opts),
-            Scope::_test_new_full(ident!(FooBar), "baz", vec!["baz"], &opts.separator, &opts.prefix, vec![])
+            Scope::_test_new_full(ident!(FooBar), vec!["baz"], opts.separator(), &opts.prefix, vec![])
         );
 
         let variant_opts = ScopeVariantOpts {
             ident: ident!(FooBar),
             rename: Some("baz.bar".to_string()),
             #[cfg(feature = "hierarchy")]
-            include: Some(IncludeList(Vec::new())),
+            include: Some(VariantList(Vec::new())),
+            ..Default::default()
         };
         assert_eq!(
             Scope::from_variant(&variant_opts, &opts),
-            Scope::_test_new_full(ident!(FooBar), "baz.bar", vec!["baz", "bar"], &opts.separator, &opts.prefix, vec![])
+            Scope::_test_new_full(ident!(FooBar), vec!["baz", "bar"], opts.separator(), &opts.prefix, vec![])
         );
     }
 
+    #[test]
+    fn test_description_from_doc() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote!(#[doc = " Read the profile"]),
+            syn::parse_quote!(#[doc = " of the user  "]),
+            syn::parse_quote!(#[allow(unused)]),
+        ];
+
+        assert_eq!(Some("Read the profile\nof the user".to_string()), get_description_from_doc(&attrs));
+        assert_eq!(None, get_description_from_doc(&[syn::parse_quote!(#[doc = "  "])]));
+        assert_eq!(None, get_description_from_doc(&[]));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let opts = default_opts();
+
+        let documented = ScopeVariantOpts {
+            attrs: vec![syn::parse_quote!(#[doc = " Documented scope"])],
+            title: Some("Title".to_string()),
+            ..Default::default()
+        };
+        let described = ScopeVariantOpts {
+            attrs: vec![syn::parse_quote!(#[doc = " Documented scope"])],
+            description: Some("Described scope".to_string()),
+            ..Default::default()
+        };
+
+        let documented = Scope::from_variant(&documented, &opts).metadata;
+        let described = Scope::from_variant(&described, &opts).metadata;
+
+        assert_eq!(Some("Documented scope".to_string()), documented.description);
+        assert_eq!(Some("Title".to_string()), documented.title);
+        assert_eq!(Some("Described scope".to_string()), described.description);
+        assert_eq!(None, described.title);
+    }
+
     #[test]
     fn test_name() {
         let mut opts = default_opts();
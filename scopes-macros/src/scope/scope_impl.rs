@@ -5,7 +5,15 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 
-use crate::{Scope, ScopeOpts};
+use crate::{Scope, ScopeOpts, diagnostic::Warning, scope::{expand, request, template::{self, CONSTRUCTOR_PREFIX, param_binding}}};
+
+// Convert an optional string to an `Option<&'static str>` expression
+fn quote_option(value: &Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
 
 
 pub fn derive_into_scope_impl(opts: &ScopeOpts) -> TokenStream {
@@ -20,90 +28,578 @@ pub fn derive_into_scope_impl(opts: &ScopeOpts) -> TokenStream {
         },
     };
 
-    // Parse scope names
-    let mut scopes: HashMap<String, Scope> = HashMap::with_capacity(variants.len());
+    // Parse scope names, keeping the declaration order of the variants
+    let mut scopes: Vec<Scope> = Vec::with_capacity(variants.len());
+    let mut scopes_by_name: HashMap<String, usize> = HashMap::with_capacity(variants.len());
     let mut error: Option<syn::Error> = None;
 
     for variant in variants {
-        
-        let scope = Scope::from_variant(variant, &opts);
-        let scope_full_name = scope.full_name();
-
-        // Raise error for scopes with conflicting names
-        if let Some(other_scope) = scopes.get(&scope_full_name) {
-            let mut err = syn::Error::new(
-                variant.ident.span(), 
-                format!("Conflicting scope name '{}' (conflicting with variant {}::{})", scope.name(), enum_ident, &other_scope.ident)
-            );
-
-            err.combine(syn::Error::new(
-                other_scope.ident.span(), 
-                format!("Conflicting scope name '{}' (conflicting with variant {}::{})", other_scope.name(), enum_ident, variant.ident)
-            ));
-
-            if let Some(error) = error.as_mut() {
-                error.combine(err);
-            } else {
-                error = Some(err)
+
+        let scope = Scope::from_variant(variant, opts);
+
+        let validation = if scope.other {
+            template::validate_catch_all(&scope)
+        } else if scope.internal && !scope.aliases.is_empty() {
+            Err(syn::Error::new(scope.ident.span(), "Aliases cannot be used on internal scopes, which are never parsed"))
+        } else if scope.default && scope.is_parameterized() {
+            Err(syn::Error::new(scope.ident.span(), "Parameterized scopes cannot be default scopes"))
+        } else if scope.nested.is_some() {
+            template::validate_nested(&scope, opts.separator())
+        } else {
+            template::validate_template(&scope, opts.separator())
+        };
+
+        if let Err(err) = validation {
+            match error.as_mut() {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
             }
+        }
 
+        // The catch-all variant has no name to validate
+        if !scope.other && let Err(err) = template::validate_name(&scope, opts.validate_names) {
+            match error.as_mut() {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
+            }
+        }
+
+        // The catch-all variant has no name
+        if scope.other {
+            if let Some(other_scope) = scopes.iter().find(|s| s.other) {
+                let mut err = syn::Error::new(
+                    variant.ident.span(),
+                    format!("Only one variant can be marked with `other` (conflicting with variant {}::{})", enum_ident, other_scope.ident)
+                );
+                err.combine(syn::Error::new(
+                    other_scope.ident.span(),
+                    format!("Only one variant can be marked with `other` (conflicting with variant {}::{})", enum_ident, variant.ident)
+                ));
+
+                match error.as_mut() {
+                    Some(error) => error.combine(err),
+                    None => error = Some(err),
+                }
+            }
+
+            scopes.push(scope);
+            continue;
+        }
+
+        // Parameters are ignored when looking for conflicting templates
+        let full_name = if scope.is_parameterized() {
+            template::template_key(&scope)
         } else {
-            scopes.insert(scope_full_name, scope);
+            scope.full_name()
+        };
+
+        // The scope name and its aliases, with and without the prefix
+        let names = std::iter::once((scope.name(), full_name))
+            .chain(scope.aliases.iter().cloned().zip(scope.full_aliases()));
+
+        for (name, full_name) in names {
+
+            let key = opts.parse_key(&full_name);
+
+            // Raise error for scopes with conflicting names
+            if let Some(other_scope) = scopes_by_name.get(&key).map(|i| scopes.get(*i).unwrap_or(&scope)) {
+                let mut err = syn::Error::new(
+                    variant.ident.span(),
+                    format!("Conflicting scope name '{}' (conflicting with variant {}::{})", name, enum_ident, &other_scope.ident)
+                );
+
+                err.combine(syn::Error::new(
+                    other_scope.ident.span(),
+                    format!("Conflicting scope name '{}' (conflicting with variant {}::{})", name, enum_ident, variant.ident)
+                ));
+
+                if let Some(error) = error.as_mut() {
+                    error.combine(err);
+                } else {
+                    error = Some(err)
+                }
+
+            } else {
+                scopes_by_name.insert(key, scopes.len());
+            }
+        }
+
+        scopes.push(scope);
+    }
+
+    // The scope name of parameterized scopes is not known at compile time
+    if opts.scope_name_getter {
+        for scope in scopes.iter().filter(|s| s.is_parameterized() && s.nested.is_none()) {
+            let err = syn::Error::new(scope.ident.span(), "`scope_name_getter` cannot be used with parameterized scopes or the `other` variant");
+            match error.as_mut() {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
+            }
+        }
+    }
+
+    if let Err(err) = request::validate_targets(enum_ident, &scopes) {
+        match error.as_mut() {
+            Some(error) => error.combine(err),
+            None => error = Some(err),
+        }
+    }
+
+    if let Err(err) = expand::validate_groups(enum_ident, &scopes) {
+        match error.as_mut() {
+            Some(error) => error.combine(err),
+            None => error = Some(err),
+        }
+    }
+
+    // Warnings emitted with the generated code
+    #[cfg_attr(not(feature = "hierarchy"), allow(unused_mut))]
+    let mut warnings: Vec<Warning> = Vec::new();
+
+    #[cfg(feature = "hierarchy")]
+    if let Err(err) = crate::hierarchy::validate_includes(enum_ident, &scopes, &mut warnings) {
+        match error.as_mut() {
+            Some(error) => error.combine(err),
+            None => error = Some(err),
         }
+    }
 
+    #[cfg(feature = "hierarchy")]
+    if opts.hierarchy && let Err(err) = crate::hierarchy::validate_hierarchy(&scopes, opts.allow_cycles) {
+        match error.as_mut() {
+            Some(error) => error.combine(err),
+            None => error = Some(err),
+        }
     }
 
     if let Some(err) = error {
         return err.into_compile_error().into();
     }
 
+    // Split unit scopes and parameterized scopes
+    let catch_all = scopes.iter().find(|s| s.other);
+    let (templates, unit_scopes): (Vec<&Scope>, Vec<&Scope>) = scopes.iter()
+        .filter(|s| !s.other)
+        .partition(|s| s.is_parameterized());
+
     // Implement parsing from a string
 
-    let (scopes_full_names, scopes_ident): (Vec<_>, Vec<_>) = scopes
+    let (scopes_full_names, scopes_ident): (Vec<_>, Vec<_>) = unit_scopes
         .iter()
-        .map(|(k, v)| (k, &v.ident))
+        .map(|s| (s.full_name(), &s.ident))
         .unzip();
 
+    // Names and aliases matched when parsing
+    let (parse_keys, parse_idents): (Vec<_>, Vec<_>) = unit_scopes
+        .iter()
+        .filter(|s| !s.internal)
+        .flat_map(|s| std::iter::once(s.full_name()).chain(s.full_aliases()).map(move |name| (name, &s.ident)))
+        .map(|(name, ident)| (opts.parse_key(&name), ident))
+        .unzip();
+
+    // Normalize the input before matching it
+    let normalize_input = match &opts.normalize {
+        Some(normalize) => quote! {
+            let normalized = #normalize(s);
+            let s: &str = ::std::convert::AsRef::<str>::as_ref(&normalized);
+        },
+        None => quote! {},
+    };
+
+    // Parameters keep their case, so templates are parsed from the trimmed input
+    let case_insensitive_input = if opts.case_insensitive {
+        quote! {
+            let template_input = s.trim();
+            let lowercase = template_input.to_ascii_lowercase();
+            let s: &str = &lowercase;
+        }
+    } else {
+        quote! {
+            let template_input = s;
+        }
+    };
+
+    // Parameterized and nested scopes are tried in declaration order when no name matches
+    let parse_templates = templates.iter().filter(|s| !s.internal).map(|scope| match scope.nested {
+        Some(_) => template::parse_nested(enum_ident, scope, opts.case_insensitive),
+        None => template::parse_template(enum_ident, scope, opts.case_insensitive),
+    });
+
+    // Unknown scopes are kept as is in the catch-all variant
+    let parse_unknown = match catch_all {
+        Some(scope) => {
+            let param = scope.fields.param_names().remove(0);
+            let binding = param_binding(CONSTRUCTOR_PREFIX, &param);
+            let constructor = scope.fields.constructor(enum_ident, &scope.ident, CONSTRUCTOR_PREFIX);
+            quote! {{
+                let #binding = ::std::convert::From::from(input);
+                Ok(#constructor)
+            }}
+        },
+        None => quote! {
+            Err(::scopes_rs::error::ScopeParseError(input.to_string()))
+        },
+    };
+
+    let parse_fallback = if templates.iter().all(|s| s.internal) {
+        parse_unknown
+    } else {
+        quote! {{
+            #(#parse_templates)*
+            #parse_unknown
+        }}
+    };
+
     let fromstr_impl = quote! {
         impl ::std::str::FromStr for #enum_ident {
             type Err = ::scopes_rs::error::ScopeParseError;
 
-            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+            fn from_str(input: &str) -> ::std::result::Result<Self, Self::Err> {
+                let s = input;
+                #normalize_input
+                #case_insensitive_input
+
                 match s {
-                    #(#scopes_full_names => Ok(#enum_ident::#scopes_ident),)*
-                    _ => Err(::scopes_rs::error::ScopeParseError(s.to_string())),
+                    #(#parse_keys => Ok(#enum_ident::#parse_idents),)*
+                    _ => #parse_fallback,
                 }
             }
         }
     };
 
-    // Implement scope_name() function
-    let scope_name_impl;
-    if opts.scope_name_getter {
+    // Implement Display with the scope name, rendering the parameters of templates
+    let display_templates = templates.iter().map(|scope| {
+        let (format, params) = template::format_string(scope);
+        let pattern = scope.fields.pattern(enum_ident, &scope.ident, &params, CONSTRUCTOR_PREFIX);
+        let bindings = params.iter().map(|p| param_binding(CONSTRUCTOR_PREFIX, p));
+
+        quote! { #pattern => write!(f, #format, #(#bindings),*) }
+    });
+
+    let display_unknown = catch_all.map(|scope| {
+        let params = scope.fields.param_names();
+        let params: Vec<&str> = params.iter().map(String::as_str).collect();
+        let pattern = scope.fields.pattern(enum_ident, &scope.ident, &params, CONSTRUCTOR_PREFIX);
+        let binding = param_binding(CONSTRUCTOR_PREFIX, params[0]);
 
-        let vis = &opts.vis;
+        quote! { #pattern => ::std::fmt::Display::fmt(#binding, f), }
+    });
+
+    let display_impl = if opts.display {
+        quote! {
+            impl ::std::fmt::Display for #enum_ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #(#enum_ident::#scopes_ident => f.write_str(#scopes_full_names),)*
+                        #(#display_templates,)*
+                        #display_unknown
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let vis = &opts.vis;
+
+    // Scopes of the nested scope enums, prefixed with the labels of their variant
+    let nested_scopes: Vec<&Scope> = templates.iter().filter(|s| s.nested.is_some()).copied().collect();
+    let nested_binding = param_binding(CONSTRUCTOR_PREFIX, "0");
+
+    // Implement scope_name() function.
+    // The name of a nested scope is built from the name of the wrapped scope
+    let scope_name_impl = if opts.scope_name_getter {
+        let nested_names = nested_scopes.iter().map(|scope| {
+            let pattern = scope.fields.pattern(enum_ident, &scope.ident, &["0"], CONSTRUCTOR_PREFIX);
+            let prefix = template::nested_prefix(scope);
+            quote! {
+                #pattern => ::std::borrow::Cow::Owned(::std::format!("{}{}", #prefix, #nested_binding.scope_name())),
+            }
+        });
 
-        scope_name_impl = quote! {
+        quote! {
             impl #enum_ident {
-                #vis const fn scope_name(&self) -> &'static str {
+                #vis fn scope_name(&self) -> ::std::borrow::Cow<'static, str> {
                     match self {
-                        #(#enum_ident::#scopes_ident => #scopes_full_names,)*
+                        #(#enum_ident::#scopes_ident => ::std::borrow::Cow::Borrowed(#scopes_full_names),)*
+                        #(#nested_names)*
                     }
                 }
             }
         }
     } else {
-        scope_name_impl = quote! {}
-    }
+        quote! {}
+    };
+
+    // Implement the enumeration of all variants.
+    // Variants without fields are indexed by their position in `ALL`, and variants with fields have
+    // the indices after them, so they can be used with `METADATA`
+    let indexed_scopes = Scope::index_order(&scopes);
+    let scopes_count = unit_scopes.len();
+    let scopes_patterns = indexed_scopes.iter().map(|s| s.fields.pattern(enum_ident, &s.ident, &[], CONSTRUCTOR_PREFIX));
+    let scopes_index: Vec<usize> = (0..indexed_scopes.len()).collect();
+    let unit_scopes_index: Vec<usize> = (0..scopes_count).collect();
+
+    let internal_patterns: Vec<_> = scopes.iter()
+        .filter(|s| s.internal)
+        .map(|s| s.fields.pattern(enum_ident, &s.ident, &[], CONSTRUCTOR_PREFIX))
+        .collect();
+
+    let is_internal = if internal_patterns.is_empty() {
+        quote! { false }
+    } else {
+        quote! { matches!(self, #(#internal_patterns)|*) }
+    };
+
+    // The scopes of the nested scope enums are listed with the prefix of their variant, in declaration order
+    let published = scopes.iter().filter(|s| !s.internal && (!s.is_parameterized() || s.nested.is_some()));
+    let push_names = published.map(|scope| match &scope.nested {
+        Some(ty) => {
+            let prefix = template::nested_prefix(scope);
+            quote! {
+                names.extend(<#ty>::scopes_supported().into_iter().map(|name| {
+                    ::std::borrow::Cow::Owned(::std::format!("{}{}", #prefix, name))
+                }));
+            }
+        },
+        None => {
+            let name = scope.full_name();
+            quote! { names.push(::std::borrow::Cow::Borrowed(#name)); }
+        },
+    });
+
+    let granted = scopes.iter().filter(|s| s.default || (s.nested.is_some() && !s.internal));
+    let push_defaults = granted.map(|scope| {
+        let ident = &scope.ident;
+        match &scope.nested {
+            Some(ty) => quote! { defaults.extend(<#ty>::defaults().into_iter().map(#enum_ident::#ident)); },
+            None => quote! { defaults.push(#enum_ident::#ident); },
+        }
+    });
+
+    let lists_impl = quote! {
+        impl #enum_ident {
+            /// Get the names of the scopes that can be parsed, without the internal and parameterized scopes,
+            /// including the scopes of the nested scope enums
+            #vis fn scopes_supported() -> ::std::vec::Vec<::std::borrow::Cow<'static, str>> {
+                let mut names = ::std::vec::Vec::new();
+                #(#push_names)*
+                names
+            }
+
+            /// Get the scopes granted when no scope is requested, in declaration order, including the default
+            /// scopes of the nested scope enums
+            #vis fn defaults() -> ::std::vec::Vec<Self> {
+                let mut defaults = ::std::vec::Vec::new();
+                #(#push_defaults)*
+                defaults
+            }
+        }
+    };
+
+    let enumerate_impl = quote! {
+        impl #enum_ident {
+            /// Every scope variant without parameters, in declaration order
+            #vis const ALL: &'static [Self] = &[#(#enum_ident::#scopes_ident,)*];
+
+            /// The number of scope variants without parameters, which is the length of [`Self::ALL`]
+            #vis const COUNT: usize = #scopes_count;
+
+            /// Get the index of this scope in [`Self::ALL`], or an index after [`Self::COUNT`] for the
+            /// variants with parameters
+            #vis const fn index(&self) -> usize {
+                match self {
+                    #(#scopes_patterns => #scopes_index,)*
+                }
+            }
+
+            /// Get the scope at the given index in [`Self::ALL`]
+            #vis const fn from_index(index: usize) -> ::std::option::Option<Self> {
+                match index {
+                    #(#unit_scopes_index => ::std::option::Option::Some(#enum_ident::#scopes_ident),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            /// Check if this scope is internal, and can't be parsed from a string
+            #vis const fn is_internal(&self) -> bool {
+                #is_internal
+            }
+        }
+
+        #lists_impl
+
+        impl ::scopes_rs::list::DefaultScopes for #enum_ident {
+            fn defaults() -> ::std::vec::Vec<Self> {
+                #enum_ident::defaults()
+            }
+        }
+    };
+
+    // Implement the metadata table
+    let scopes_metadata = indexed_scopes.iter().map(|scope| {
+        // The name of parameterized scopes is their template
+        let name = scope.full_name();
+        let description = quote_option(&scope.metadata.description);
+        let title = quote_option(&scope.metadata.title);
+        let category = quote_option(&scope.metadata.category);
+        let icon = quote_option(&scope.metadata.icon);
+
+        quote! {
+            ::scopes_rs::metadata::ScopeMetadata {
+                name: #name,
+                description: #description,
+                title: #title,
+                category: #category,
+                icon: #icon,
+            }
+        }
+    });
+
+    let metadata_impl = quote! {
+        impl #enum_ident {
+            /// The metadata of every scope variant, indexed by [`Self::index`]
+            #vis const METADATA: &'static [::scopes_rs::metadata::ScopeMetadata] = &[#(#scopes_metadata,)*];
+
+            /// Get the metadata of this scope
+            #vis const fn metadata(&self) -> &'static ::scopes_rs::metadata::ScopeMetadata {
+                &Self::METADATA[self.index()]
+            }
+
+            /// Get the human-readable description of this scope
+            #vis const fn description(&self) -> ::std::option::Option<&'static str> {
+                self.metadata().description
+            }
+        }
+    };
+
+    // Implement wildcard grants matching the labels of the scopes
+    let wildcard_impl = if opts.wildcard {
+        let prefix = &opts.prefix;
+        let separator = opts.separator();
+        let case_insensitive = opts.case_insensitive;
+
+        // Only the prefix ignores the case, the labels keep their case to compare the parameters exactly
+        let strip_prefix = if opts.case_insensitive {
+            quote! {
+                let s = s.trim();
+                let s = s.get(..#prefix.len()).filter(|p| p.eq_ignore_ascii_case(#prefix)).and_then(|_| s.get(#prefix.len()..))?;
+            }
+        } else {
+            quote! { let s = s.strip_prefix(#prefix)?; }
+        };
+
+        let scopes_matches = scopes.iter().map(|scope| {
+            // Wildcards never grant unknown or internal scopes
+            if scope.other || scope.internal {
+                let pattern = scope.fields.pattern(enum_ident, &scope.ident, &[], CONSTRUCTOR_PREFIX);
+                return quote! { #pattern => false };
+            }
+
+            let params: Vec<&str> = scope.params().map(|(_, p)| p).collect();
+            let pattern = scope.fields.pattern(enum_ident, &scope.ident, &params, CONSTRUCTOR_PREFIX);
+
+            // The name of a nested scope is split in labels like the other names. The labels of the wrapped
+            // scope are compared exactly, since its case sensitivity is defined by the wrapped enum
+            if scope.nested.is_some() {
+                let labels = &scope.labels[..scope.labels.len() - 1];
+                let binding = param_binding(CONSTRUCTOR_PREFIX, "0");
+                return quote! {
+                    #pattern => {
+                        let name = ::std::string::ToString::to_string(#binding);
+                        let labels: ::std::vec::Vec<::scopes_rs::wildcard::WildcardLabel> = [#(::scopes_rs::wildcard::WildcardLabel::Literal(#labels)),*].into_iter()
+                            .chain(name.split(#separator).map(::scopes_rs::wildcard::WildcardLabel::Param))
+                            .collect();
+                        wildcard.matches(&labels, #case_insensitive)
+                    }
+                };
+            }
+
+            let labels = scope.labels.iter().map(|label| match template::param_name(label) {
+                Some(param) => {
+                    let binding = param_binding(CONSTRUCTOR_PREFIX, param);
+                    quote! { ::scopes_rs::wildcard::WildcardLabel::Param(&::std::string::ToString::to_string(#binding)) }
+                },
+                None => quote! { ::scopes_rs::wildcard::WildcardLabel::Literal(#label) },
+            });
+
+            quote! { #pattern => wildcard.matches(&[#(#labels),*], #case_insensitive) }
+        });
+
+        quote! {
+            impl ::scopes_rs::wildcard::WildcardScope for #enum_ident {
+                fn parse_wildcard(input: &str) -> ::std::option::Option<::scopes_rs::wildcard::Wildcard> {
+                    let s = input;
+                    #normalize_input
+                    #strip_prefix
+
+                    ::scopes_rs::wildcard::Wildcard::parse(s, #separator)
+                }
+
+                fn matches_wildcard(&self, wildcard: &::scopes_rs::wildcard::Wildcard) -> bool {
+                    match self {
+                        #(#scopes_matches,)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let validate_request_impl = request::implement_validate_request(enum_ident, vis, &scopes);
+
+    // Nested scopes are catch-all scopes if the wrapped scope is
+    let nested_catch_all = scopes.iter().filter(|s| s.nested.is_some()).map(|scope| {
+        let pattern = scope.fields.pattern(enum_ident, &scope.ident, &["0"], CONSTRUCTOR_PREFIX);
+        let binding = param_binding(CONSTRUCTOR_PREFIX, "0");
+        quote! { #pattern => ::scopes_rs::scope::Scope::is_catch_all(#binding), }
+    });
+
+    let catch_all_impl = match catch_all {
+        Some(scope) => {
+            let pattern = scope.fields.pattern(enum_ident, &scope.ident, &[], CONSTRUCTOR_PREFIX);
+            quote! {
+                fn is_catch_all(&self) -> bool {
+                    match self {
+                        #(#nested_catch_all)*
+                        #pattern => true,
+                        _ => false,
+                    }
+                }
+            }
+        },
+        None if scopes.iter().any(|s| s.nested.is_some()) => quote! {
+            fn is_catch_all(&self) -> bool {
+                match self {
+                    #(#nested_catch_all)*
+                    _ => false,
+                }
+            }
+        },
+        None => quote! {},
+    };
+
+    let expansion_impl = expand::implement_expansion(enum_ident, &scopes);
 
     let scope_impl = quote! {
-        impl ::scopes_rs::scope::Scope for #enum_ident {}
+        impl ::scopes_rs::scope::Scope for #enum_ident {
+            #catch_all_impl
+            #expansion_impl
+        }
     };
 
     let scope_impl = quote! {
         #fromstr_impl
+        #display_impl
         #scope_name_impl
+        #enumerate_impl
+        #metadata_impl
+        #wildcard_impl
+        #validate_request_impl
         #scope_impl
+        #(#warnings)*
     };
 
     #[cfg(feature = "hierarchy")]
@@ -116,7 +612,7 @@ pub fn derive_into_scope_impl(opts: &ScopeOpts) -> TokenStream {
 
         use crate::hierarchy::implement_hierarchized;
 
-        scope_impl.append_all(implement_hierarchized(enum_ident, &scopes));
+        scope_impl.append_all(implement_hierarchized(enum_ident, &scopes, opts.inclusion_table));
     }
 
     scope_impl.into()
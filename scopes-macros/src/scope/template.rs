@@ -0,0 +1,522 @@
+use darling::{FromField, ast};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::Scope;
+
+// Options for a field of a parameterized scope variant
+#[derive(Debug, FromField)]
+pub struct ScopeFieldOpts {
+    pub ident: Option<syn::Ident>,
+    pub ty: syn::Type,
+}
+
+// Fields of a scope variant
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum VariantFields {
+    #[default]
+    Unit,
+
+    // Number of fields of a tuple variant
+    Tuple(usize),
+
+    // Names of the fields of a struct variant
+    Named(Vec<Ident>),
+}
+
+impl VariantFields {
+
+    pub fn from_fields(fields: &ast::Fields<ScopeFieldOpts>) -> Self {
+        match fields.style {
+            ast::Style::Unit => VariantFields::Unit,
+            ast::Style::Tuple => VariantFields::Tuple(fields.len()),
+            ast::Style::Struct => VariantFields::Named(
+                fields.iter().filter_map(|f| f.ident.clone()).collect()
+            ),
+        }
+    }
+
+    pub fn is_unit(&self) -> bool {
+        matches!(self, VariantFields::Unit)
+    }
+
+    // Names of the parameters that can be used in a name template:
+    // the position of the fields of a tuple variant, or the names of the fields
+    pub fn param_names(&self) -> Vec<String> {
+        match self {
+            VariantFields::Unit => Vec::new(),
+            VariantFields::Tuple(n) => (0..*n).map(|i| i.to_string()).collect(),
+            VariantFields::Named(names) => names.iter().map(Ident::to_string).collect(),
+        }
+    }
+
+    // Get the member corresponding to a parameter name
+    fn member(&self, param: &str) -> syn::Member {
+        match self {
+            VariantFields::Named(_) => syn::Member::Named(format_ident!("{}", param)),
+            _ => syn::Member::Unnamed(syn::Index::from(param.parse::<usize>().unwrap_or_default())),
+        }
+    }
+
+    // Get a pattern matching the variant, where each of the given parameters
+    // is bound to the ident returned by `param_binding(binding_prefix, param)`
+    pub fn pattern(&self, enum_ident: &Ident, variant_ident: &Ident, params: &[&str], binding_prefix: &str) -> TokenStream {
+        if self.is_unit() {
+            return quote! { #enum_ident::#variant_ident };
+        }
+
+        let members = params.iter().map(|p| self.member(p));
+        let bindings = params.iter().map(|p| param_binding(binding_prefix, p));
+
+        quote! { #enum_ident::#variant_ident { #(#members: #bindings,)* .. } }
+    }
+
+    // Get an expression constructing the variant, where each field is
+    // taken from the ident returned by `param_binding(binding_prefix, param)`
+    pub fn constructor(&self, enum_ident: &Ident, variant_ident: &Ident, binding_prefix: &str) -> TokenStream {
+        if self.is_unit() {
+            return quote! { #enum_ident::#variant_ident };
+        }
+
+        let params = self.param_names();
+        let members = params.iter().map(|p| self.member(p));
+        let bindings = params.iter().map(|p| param_binding(binding_prefix, p));
+
+        quote! { #enum_ident::#variant_ident { #(#members: #bindings,)* } }
+    }
+}
+
+// Get the identifier a parameter is bound to in generated code
+pub fn param_binding(prefix: &str, param: &str) -> Ident {
+    format_ident!("__{}_{}", prefix, param)
+}
+
+// Get the parameter name of a label, if it is a placeholder like `{id}`
+pub fn param_name(label: &str) -> Option<&str> {
+    label.strip_prefix('{')?.strip_suffix('}')
+}
+
+// Get the full name of a scope where every parameter is replaced with `{}`,
+// used to detect conflicting templates
+pub fn template_key(scope: &Scope) -> String {
+    let labels: Vec<&str> = scope.labels.iter()
+        .map(|l| if param_name(l).is_some() { "{}" } else { l.as_str() })
+        .collect();
+
+    scope.prefix().to_string() + &labels.join(scope.separator())
+}
+
+// Get a format string rendering the full name of a scope, and the parameters
+// used for each of its arguments
+pub fn format_string(scope: &Scope) -> (String, Vec<&str>) {
+    let escape = |s: &str| s.replace('{', "{{").replace('}', "}}");
+
+    let labels: Vec<String> = scope.labels.iter()
+        .map(|l| if param_name(l).is_some() { "{}".to_string() } else { escape(l) })
+        .collect();
+
+    let format = escape(scope.prefix()) + &labels.join(&escape(scope.separator()));
+    (format, scope.params().map(|(_, p)| p).collect())
+}
+
+// Generate code parsing a parameterized scope from `template_input`, and returning it if it matches
+pub fn parse_template(enum_ident: &Ident, scope: &Scope, case_insensitive: bool) -> TokenStream {
+
+    let prefix = scope.prefix();
+    let separator = scope.separator();
+    let labels_count = scope.labels.len();
+
+    let strip_prefix = if case_insensitive {
+        quote! {
+            template_input.get(..#prefix.len())
+                .filter(|p| p.eq_ignore_ascii_case(#prefix))
+                .and_then(|_| template_input.get(#prefix.len()..))
+        }
+    } else {
+        quote! { template_input.strip_prefix(#prefix) }
+    };
+
+    // Literal labels must match exactly, and parameters must not be empty
+    let checks = scope.labels.iter().enumerate().map(|(i, label)| match param_name(label) {
+        Some(_) => quote! { !labels[#i].is_empty() },
+        None if case_insensitive => quote! { labels[#i].eq_ignore_ascii_case(#label) },
+        None => quote! { labels[#i] == #label },
+    });
+
+    let (positions, params): (Vec<usize>, Vec<&str>) = scope.params().unzip();
+    let bindings: Vec<Ident> = params.iter().map(|p| param_binding(CONSTRUCTOR_PREFIX, p)).collect();
+    let constructor = scope.fields.constructor(enum_ident, &scope.ident, CONSTRUCTOR_PREFIX);
+
+    // Parsed values must be displayed as a single label, so the scope is displayed as the name it was parsed from
+    let value_checks = bindings.iter().map(|binding| quote! {
+        is_label(&::std::string::ToString::to_string(&#binding))
+    });
+
+    quote! {
+        if let Some(name) = #strip_prefix {
+            let labels: ::std::vec::Vec<&str> = name.split(#separator).collect();
+            if labels.len() == #labels_count #(&& #checks)* {
+                if let (#(::std::result::Result::Ok(#bindings),)*) = (#(labels[#positions].parse(),)*) {
+                    let is_label = |value: &str| !value.is_empty() && !value.contains(#separator);
+                    if #(#value_checks)&&* {
+                        return ::std::result::Result::Ok(#constructor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Get the part of the full name of a nested scope before the name of the wrapped scope, like `billing.`
+pub fn nested_prefix(scope: &Scope) -> String {
+    let labels = &scope.labels[..scope.labels.len().saturating_sub(1)];
+    scope.prefix().to_string() + &labels.join(scope.separator()) + scope.separator()
+}
+
+// Generate the code parsing a nested scope from the variable `template_input`,
+// delegating the rest of the input to the wrapped scope enum
+pub fn parse_nested(enum_ident: &Ident, scope: &Scope, case_insensitive: bool) -> TokenStream {
+
+    let prefix = nested_prefix(scope);
+
+    let strip_prefix = if case_insensitive {
+        quote! {
+            template_input.get(..#prefix.len())
+                .filter(|p| p.eq_ignore_ascii_case(#prefix))
+                .and_then(|_| template_input.get(#prefix.len()..))
+        }
+    } else {
+        quote! { template_input.strip_prefix(#prefix) }
+    };
+
+    let binding = param_binding(CONSTRUCTOR_PREFIX, "0");
+    let constructor = scope.fields.constructor(enum_ident, &scope.ident, CONSTRUCTOR_PREFIX);
+
+    quote! {
+        if let Some(name) = #strip_prefix {
+            if let ::std::result::Result::Ok(#binding) = name.parse() {
+                return ::std::result::Result::Ok(#constructor);
+            }
+        }
+    }
+}
+
+// Prefix of the bindings used to construct a variant
+pub const CONSTRUCTOR_PREFIX: &str = "param";
+
+// Check if the labels of a scope are a prefix of the labels of another one.
+// A parameter label matches any other parameter label.
+#[cfg(feature = "hierarchy")]
+pub fn labels_include(labels: &[String], other: &[String]) -> bool {
+    labels.len() <= other.len()
+        && labels.iter().zip(other).all(|(a, b)| match (param_name(a), param_name(b)) {
+            (Some(_), Some(_)) => true,
+            (None, None) => a == b,
+            _ => false,
+        })
+}
+
+// Check that the name template of a scope is valid for its fields
+pub fn validate_template(scope: &Scope, separator: &str) -> Result<(), syn::Error> {
+
+    let error = |msg: String| Err(syn::Error::new(scope.ident.span(), msg));
+
+    let field_names = scope.fields.param_names();
+
+    if scope.fields.is_unit() {
+        if scope.params().next().is_some() {
+            return error("Name templates can only be used on variants with fields".to_string());
+        }
+        return Ok(());
+    }
+
+    if !scope.renamed {
+        return error(format!(
+            "Scope variants with fields require a name template, like `#[scope(rename = \"{}{}{{{}}}\")]`",
+            scope.name(), separator, field_names.first().map(String::as_str).unwrap_or_default(),
+        ));
+    }
+
+    if separator.is_empty() {
+        return error("Scope variants with fields require a non-empty separator".to_string());
+    }
+
+    if !scope.aliases.is_empty() {
+        return error("Aliases cannot be used on scope variants with fields".to_string());
+    }
+
+    // Placeholders must be whole labels
+    if let Some(label) = scope.labels.iter().find(|l| param_name(l).is_none() && (l.contains('{') || l.contains('}'))) {
+        return error(format!("Invalid label '{}' in name template: a parameter must be a whole label like `{{id}}`", label));
+    }
+
+    let params: Vec<&str> = scope.params().map(|(_, p)| p).collect();
+
+    for (i, param) in params.iter().enumerate() {
+        if !field_names.iter().any(|f| f == param) {
+            return error(format!("Unknown parameter '{{{}}}' in name template, expected one of: {}", param, format_params(&field_names)));
+        }
+        if params[..i].contains(param) {
+            return error(format!("Parameter '{{{}}}' is used multiple times in the name template", param));
+        }
+    }
+
+    if let Some(field) = field_names.iter().find(|f| !params.contains(&f.as_str())) {
+        return error(format!("Field '{}' is not used in the name template", field));
+    }
+
+    Ok(())
+}
+
+// Check that a nested scope wraps a single scope enum, and that its name has no parameters
+pub fn validate_nested(scope: &Scope, separator: &str) -> Result<(), syn::Error> {
+
+    let error = |msg: &str| Err(syn::Error::new(scope.ident.span(), msg));
+
+    if !matches!(scope.fields, VariantFields::Tuple(1)) {
+        return error("Nested scopes must wrap a single scope enum, like `Billing(BillingScope)`");
+    }
+
+    if separator.is_empty() {
+        return error("Nested scopes require a non-empty separator");
+    }
+
+    if !scope.aliases.is_empty() {
+        return error("Aliases cannot be used on nested scopes");
+    }
+
+    let labels = &scope.labels[..scope.labels.len() - 1];
+    if labels.iter().any(|l| l.contains('{') || l.contains('}')) {
+        return error("The name of a nested scope cannot contain parameters, the name of the wrapped scope is added after it");
+    }
+
+    Ok(())
+}
+
+// Check that the catch-all variant stores the unknown scope in a single field
+pub fn validate_catch_all(scope: &Scope) -> Result<(), syn::Error> {
+
+    let error = |msg: &str| Err(syn::Error::new(scope.ident.span(), msg));
+
+    if scope.fields.param_names().len() != 1 {
+        return error("The `other` variant must have a single field containing the unknown scope, like `Unknown(String)`");
+    }
+
+    if scope.renamed || !scope.aliases.is_empty() {
+        return error("The `other` variant cannot have a name or aliases");
+    }
+
+    if scope.nested.is_some() {
+        return error("The `other` variant cannot be a nested scope");
+    }
+
+    if scope.internal || scope.default {
+        return error("The `other` variant cannot be internal or a default scope");
+    }
+
+    #[cfg(feature = "hierarchy")]
+    if !scope.include.is_empty() || !scope.parents.is_empty() {
+        return error("The `other` variant cannot include other scopes or have a parent");
+    }
+
+    Ok(())
+}
+
+// Check if a character is allowed in an OAuth 2.0 scope token (RFC 6749 section 3.3)
+fn is_scope_token_char(c: char) -> bool {
+    matches!(c, '\x21' | '\x23'..='\x5B' | '\x5D'..='\x7E')
+}
+
+// Check that the name and aliases of a scope are not empty and don't contain empty labels,
+// and optionally that they are valid OAuth 2.0 scope tokens
+pub fn validate_name(scope: &Scope, scope_tokens: bool) -> Result<(), syn::Error> {
+
+    let error = |msg: String| Err(syn::Error::new(scope.ident.span(), msg));
+
+    let names = std::iter::once(scope.name()).chain(scope.aliases.iter().cloned());
+
+    for name in names {
+        let full_name = scope.prefix().to_string() + &name;
+
+        if full_name.is_empty() {
+            return error("Scope names cannot be empty".to_string());
+        }
+
+        if !scope.separator().is_empty() && name.split(scope.separator()).any(str::is_empty) {
+            return error(format!("Empty label in scope name '{}'", full_name));
+        }
+
+        if scope_tokens && let Some(c) = full_name.chars().find(|c| !is_scope_token_char(*c)) {
+            return error(format!(
+                "Invalid character {:?} in scope name '{}': OAuth 2.0 scopes can only contain printable ASCII characters \
+                except spaces, '\"' and '\\'. Use `#[scope(validate_names = false)]` on the enum to allow it",
+                c, full_name,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn format_params(names: &[String]) -> String {
+    names.iter().map(|n| format!("{{{}}}", n)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use darling::FromDeriveInput;
+    use syn::parse_quote;
+
+    use crate::scope::{Scope, opts::ScopeOpts, template::{format_string, param_name, template_key, validate_name, validate_nested, validate_template}};
+
+    #[cfg(feature = "hierarchy")]
+    use crate::scope::template::labels_include;
+
+    #[cfg(feature = "hierarchy")]
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    // Parse the first variant of an enum, and validate its template
+    fn parse_scope(input: syn::DeriveInput) -> (Scope, Result<(), syn::Error>) {
+        let opts = ScopeOpts::from_derive_input(&input).expect("Could not parse enum options");
+        let variant = opts.data.as_ref().take_enum().expect("Expected an enum").remove(0);
+        let scope = Scope::from_variant(variant, &opts);
+        let result = validate_template(&scope, opts.separator());
+        (scope, result)
+    }
+
+    #[test]
+    fn test_format_string() {
+        let (scope, result) = parse_scope(parse_quote! {
+            #[scope(prefix = "{api}/", separator = ":")]
+            enum MyScope {
+                #[scope(rename = "repo:{0}:issues:{1}")]
+                RepoIssue(String, u32),
+            }
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(vec![(1, "0"), (3, "1")], scope.params().collect::<Vec<_>>());
+        assert_eq!(("{{api}}/repo:{}:issues:{}".to_string(), vec!["0", "1"]), format_string(&scope));
+        assert_eq!("{api}/repo:{}:issues:{}", template_key(&scope));
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let (_, result) = parse_scope(parse_quote! {
+            enum MyScope {
+                #[scope(rename = "org.{slug}.team.{team}")]
+                Team { slug: String, team: u32 },
+            }
+        });
+        assert!(result.is_ok());
+
+        let invalid: Vec<syn::DeriveInput> = vec![
+            // Missing template
+            parse_quote! { enum MyScope { Repo(String) } },
+            // Template on a unit variant
+            parse_quote! { enum MyScope { #[scope(rename = "repo.{0}")] Repo } },
+            // Unknown parameter
+            parse_quote! { enum MyScope { #[scope(rename = "repo.{1}")] Repo(String) } },
+            // Unused field
+            parse_quote! { enum MyScope { #[scope(rename = "repo.{0}")] Repo(String, u32) } },
+            // Duplicate parameter
+            parse_quote! { enum MyScope { #[scope(rename = "repo.{0}.{0}")] Repo(String) } },
+            // Partial label
+            parse_quote! { enum MyScope { #[scope(rename = "repo.id-{0}")] Repo(String) } },
+            // Aliases
+            parse_quote! { enum MyScope { #[scope(rename = "repo.{0}", alias = "r.{0}")] Repo(String) } },
+            // Empty separator
+            parse_quote! { #[scope(separator = "")] enum MyScope { #[scope(rename = "repo{0}")] Repo(String) } },
+        ];
+
+        for input in invalid {
+            let (_, result) = parse_scope(input.clone());
+            assert!(result.is_err(), "Template should be invalid: {}", quote::quote!(#input));
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        let validate = |input: syn::DeriveInput| {
+            let opts = ScopeOpts::from_derive_input(&input).expect("Could not parse enum options");
+            let variant = opts.data.as_ref().take_enum().expect("Expected an enum").remove(0);
+            validate_name(&Scope::from_variant(variant, &opts), opts.validate_names)
+        };
+
+        assert!(validate(parse_quote! { enum MyScope { #[scope(rename = "repo:{0}!")] Repo(String) } }).is_ok());
+        assert!(validate(parse_quote! { #[scope(prefix = "https://api.example.com/")] enum MyScope { Foo } }).is_ok());
+        assert!(validate(parse_quote! { #[scope(validate_names = false)] enum MyScope { #[scope(rename = "foo bar")] Foo } }).is_ok());
+
+        let invalid: Vec<syn::DeriveInput> = vec![
+            // Space
+            parse_quote! { enum MyScope { #[scope(rename = "foo bar")] Foo } },
+            // Double quote in the prefix
+            parse_quote! { #[scope(prefix = "\"")] enum MyScope { Foo } },
+            // Backslash in an alias
+            parse_quote! { enum MyScope { #[scope(alias = "foo\\bar")] Foo } },
+            // Non-ASCII character
+            parse_quote! { enum MyScope { Café } },
+            // Empty name
+            parse_quote! { #[scope(validate_names = false)] enum MyScope { #[scope(rename = "")] Foo } },
+            // Empty label
+            parse_quote! { #[scope(validate_names = false)] enum MyScope { #[scope(rename = "foo..bar")] Foo } },
+            parse_quote! { enum MyScope { #[scope(alias = "foo.")] Foo } },
+        ];
+
+        for input in invalid {
+            assert!(validate(input.clone()).is_err(), "Name should be invalid: {}", quote::quote!(#input));
+        }
+    }
+
+    #[test]
+    fn test_validate_nested() {
+        let validate = |input: syn::DeriveInput| {
+            let opts = ScopeOpts::from_derive_input(&input).expect("Could not parse enum options");
+            let variant = opts.data.as_ref().take_enum().expect("Expected an enum").remove(0);
+            validate_nested(&Scope::from_variant(variant, &opts), opts.separator())
+        };
+
+        assert!(validate(parse_quote! { enum MyScope { #[scope(nested)] Billing(BillingScope) } }).is_ok());
+        assert!(validate(parse_quote! { enum MyScope { #[scope(nested, rename = "api.billing")] Billing(BillingScope) } }).is_ok());
+
+        let invalid: Vec<syn::DeriveInput> = vec![
+            // No wrapped scope
+            parse_quote! { enum MyScope { #[scope(nested)] Billing } },
+            // Several fields
+            parse_quote! { enum MyScope { #[scope(nested)] Billing(BillingScope, String) } },
+            // Empty separator
+            parse_quote! { #[scope(separator = "")] enum MyScope { #[scope(nested)] Billing(BillingScope) } },
+            // Alias
+            parse_quote! { enum MyScope { #[scope(nested, alias = "bills")] Billing(BillingScope) } },
+            // Parameter in the name
+            parse_quote! { enum MyScope { #[scope(nested, rename = "billing.{0}")] Billing(BillingScope) } },
+        ];
+
+        for input in invalid {
+            assert!(validate(input.clone()).is_err(), "Nested scope should be invalid: {}", quote::quote!(#input));
+        }
+    }
+
+    #[test]
+    fn test_param_name() {
+        assert_eq!(Some("0"), param_name("{0}"));
+        assert_eq!(Some("id"), param_name("{id}"));
+        assert_eq!(None, param_name("id"));
+        assert_eq!(None, param_name("{id"));
+    }
+
+    #[cfg(feature = "hierarchy")]
+    #[test]
+    fn test_labels_include() {
+        assert!(labels_include(&labels(&["repo", "{0}"]), &labels(&["repo", "{0}", "read"])));
+        assert!(labels_include(&labels(&["repo", "{0}"]), &labels(&["repo", "{id}"])));
+        assert!(labels_include(&labels(&["repo"]), &labels(&["repo", "{0}", "read"])));
+        assert!(!labels_include(&labels(&["repo", "{0}"]), &labels(&["repo", "main"])));
+        assert!(!labels_include(&labels(&["repo", "main"]), &labels(&["repo", "{0}"])));
+        assert!(!labels_include(&labels(&["repo", "{0}", "read"]), &labels(&["repo", "{0}"])));
+    }
+}
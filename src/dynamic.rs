@@ -0,0 +1,507 @@
+//! Scopes defined at runtime
+//!
+//! [`DynamicScope`] is a scope made of a list of labels and a separator, for scopes that are not
+//! known at compile time. It implements [`Scope`] and, with the `hierarchy` feature, [`Hierarchized`]
+//! with the same semantics as the derive macro : a scope includes every scope whose labels
+//! start with its own labels.
+//!
+//! A [`ScopeCatalog`] keeps track of the valid scope names, interns their labels, and with the
+//! `hierarchy` feature allows adding explicit inclusions between scopes. The scopes retrieved from a
+//! catalog share its inclusions, while scopes created with [`DynamicScope::new`] or [`FromStr`] only
+//! include scopes by their labels.
+//!
+//! ```
+//! use scopes_rs::{dynamic::ScopeCatalog, policy::IntoPolicy};
+//!
+//! let mut catalog = ScopeCatalog::new();
+//! catalog.register("files.read").unwrap();
+//! catalog.register("files.write").unwrap();
+//!
+//! let policy = catalog.parse("files.read").unwrap().into_policy();
+//! let granted = vec![catalog.parse("files.read").unwrap()];
+//!
+//! assert!(policy.verify(&granted));
+//! assert!(catalog.parse("files.delete").is_err());
+//! ```
+
+use std::{collections::{HashMap, HashSet}, fmt::{Debug, Display}, hash::Hash, str::FromStr, sync::Arc};
+
+#[cfg(feature = "hierarchy")]
+use std::sync::{PoisonError, RwLock};
+
+#[cfg(feature = "hierarchy")]
+use crate::hierarchy::Hierarchized;
+use crate::{error::ScopeParseError, scope::Scope};
+
+/// The separator used when parsing a [`DynamicScope`] with [`FromStr`]
+pub const DEFAULT_SEPARATOR: &str = ".";
+
+// Labels of a scope
+type Labels = Arc<[Arc<str>]>;
+
+// Explicit inclusions between the scopes of a catalog, as (including scope, included scope),
+// shared by the catalog and the scopes retrieved from it
+#[cfg(feature = "hierarchy")]
+type Inclusions = Arc<RwLock<Vec<(Labels, Labels)>>>;
+
+/// A scope defined at runtime, made of labels and a separator
+///
+/// Two dynamic scopes are equal if they have the same labels and separator. The catalog a scope
+/// was retrieved from is not compared : it only defines the scopes it includes with the `hierarchy`
+/// feature, so a scope from a [`ScopeCatalog`] is equal to the same scope parsed without a catalog.
+///
+/// Parsing a dynamic scope with [`FromStr`] accepts any name with non-empty labels separated by
+/// [`DEFAULT_SEPARATOR`], and the parsed scope has no explicit inclusions. Use a [`ScopeCatalog`]
+/// to only accept registered scopes and use its inclusions, or [`DynamicScope::new`] to use another
+/// separator.
+#[derive(Clone)]
+pub struct DynamicScope {
+    labels: Labels,
+    separator: Arc<str>,
+
+    // Inclusions of the catalog this scope was retrieved from
+    #[cfg(feature = "hierarchy")]
+    catalog: Option<Inclusions>,
+}
+
+// Check if labels are a prefix of other labels
+#[cfg(feature = "hierarchy")]
+fn labels_include(labels: &[Arc<str>], other: &[Arc<str>]) -> bool {
+    other.starts_with(labels)
+}
+
+// Split a scope name in labels, checking that no label is empty
+fn split_labels<'a>(name: &'a str, separator: &str) -> Result<Vec<&'a str>, ScopeParseError> {
+
+    let labels: Vec<&str> = if separator.is_empty() {
+        vec![name]
+    } else {
+        name.split(separator).collect()
+    };
+
+    if labels.iter().any(|l| l.is_empty()) {
+        return Err(ScopeParseError(name.to_string()));
+    }
+
+    Ok(labels)
+}
+
+impl DynamicScope {
+
+    /// Create a scope from a name, split in labels with a separator.
+    ///
+    /// Fails if the name or one of its labels is empty.
+    ///
+    /// ```
+    /// use scopes_rs::dynamic::DynamicScope;
+    ///
+    /// let scope = DynamicScope::new("files:read", ":").unwrap();
+    /// assert_eq!(scope.labels().collect::<Vec<_>>(), vec!["files", "read"]);
+    /// assert_eq!(scope.to_string(), "files:read");
+    ///
+    /// assert!(DynamicScope::new("files::read", ":").is_err());
+    /// ```
+    pub fn new(name: &str, separator: &str) -> Result<Self, ScopeParseError> {
+        let labels = split_labels(name, separator)?;
+        Ok(Self::from_labels(labels.into_iter().map(Arc::from).collect(), Arc::from(separator)))
+    }
+
+    fn from_labels(labels: Labels, separator: Arc<str>) -> Self {
+        Self {
+            labels,
+            separator,
+
+            #[cfg(feature = "hierarchy")]
+            catalog: None,
+        }
+    }
+
+    /// Iterate over the labels of this scope
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.labels.iter().map(AsRef::as_ref)
+    }
+
+    /// The separator between the labels of this scope
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+}
+
+impl PartialEq for DynamicScope {
+    fn eq(&self, other: &Self) -> bool {
+        self.labels == other.labels && self.separator == other.separator
+    }
+}
+
+impl Eq for DynamicScope {}
+
+impl Hash for DynamicScope {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.labels.hash(state);
+        self.separator.hash(state);
+    }
+}
+
+impl Debug for DynamicScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicScope")
+            .field("labels", &self.labels)
+            .field("separator", &self.separator)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for DynamicScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, label) in self.labels.iter().enumerate() {
+            if i > 0 {
+                f.write_str(&self.separator)?;
+            }
+            f.write_str(label)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DynamicScope {
+    type Err = ScopeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s, DEFAULT_SEPARATOR)
+    }
+}
+
+#[cfg(feature = "hierarchy")]
+impl Hierarchized for DynamicScope {
+    fn includes(&self, other: &Self) -> bool {
+        if self.separator != other.separator {
+            return false;
+        }
+
+        if labels_include(&self.labels, &other.labels) {
+            return true;
+        }
+
+        let Some(catalog) = &self.catalog else {
+            return false;
+        };
+        let inclusions = catalog.read().unwrap_or_else(PoisonError::into_inner);
+
+        // Scopes explicitly included by this scope, directly or through the scopes it includes
+        let mut roots: Vec<&Labels> = vec![&self.labels];
+        let mut i = 0;
+
+        while i < roots.len() {
+            let root = roots[i];
+
+            // Scopes with explicit inclusions included by their labels in the current root
+            let included = inclusions.iter()
+                .filter(|(scope, _)| labels_include(root, scope))
+                .map(|(_, included)| included);
+
+            for included in included {
+                if labels_include(included, &other.labels) {
+                    return true;
+                }
+                if !roots.contains(&included) {
+                    roots.push(included);
+                }
+            }
+            i += 1;
+        }
+
+        false
+    }
+}
+
+impl Scope for DynamicScope {}
+
+/// A catalog of valid [`DynamicScope`]s
+///
+/// Registered scopes can be retrieved with [`ScopeCatalog::get`] or [`ScopeCatalog::parse`],
+/// and share the memory used by their labels.
+///
+/// With the `hierarchy` feature, a scope can explicitly include another one with
+/// [`ScopeCatalog::include`], in addition to the scopes included by its labels. Inclusion is
+/// transitive. The scopes retrieved from the catalog share its inclusions, so inclusions added
+/// afterwards also apply to previously retrieved scopes. A cloned catalog has its own copy of
+/// the inclusions.
+#[derive(Debug)]
+pub struct ScopeCatalog {
+    separator: Arc<str>,
+
+    // Interned labels
+    labels: HashSet<Arc<str>>,
+
+    // Labels of the registered scopes, in registration order
+    scopes: Vec<Labels>,
+    scopes_by_name: HashMap<String, usize>,
+
+    // Explicit inclusions between registered scopes
+    #[cfg(feature = "hierarchy")]
+    inclusions: Inclusions,
+}
+
+impl Clone for ScopeCatalog {
+    fn clone(&self) -> Self {
+        Self {
+            separator: self.separator.clone(),
+            labels: self.labels.clone(),
+            scopes: self.scopes.clone(),
+            scopes_by_name: self.scopes_by_name.clone(),
+
+            #[cfg(feature = "hierarchy")]
+            inclusions: Arc::new(RwLock::new(self.inclusions.read().unwrap_or_else(PoisonError::into_inner).clone())),
+        }
+    }
+}
+
+impl Default for ScopeCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScopeCatalog {
+
+    /// Create an empty catalog using [`DEFAULT_SEPARATOR`]
+    pub fn new() -> Self {
+        Self::with_separator(DEFAULT_SEPARATOR)
+    }
+
+    /// Create an empty catalog using a custom separator between labels
+    pub fn with_separator(separator: &str) -> Self {
+        Self {
+            separator: Arc::from(separator),
+            labels: HashSet::new(),
+            scopes: Vec::new(),
+            scopes_by_name: HashMap::new(),
+
+            #[cfg(feature = "hierarchy")]
+            inclusions: Inclusions::default(),
+        }
+    }
+
+    /// The separator between the labels of the scopes of this catalog
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    // Get the interned version of a label
+    fn intern(&mut self, label: &str) -> Arc<str> {
+        if let Some(label) = self.labels.get(label) {
+            return label.clone();
+        }
+
+        let label: Arc<str> = Arc::from(label);
+        self.labels.insert(label.clone());
+        label
+    }
+
+    /// Register a valid scope name, and return the corresponding scope.
+    ///
+    /// Registering a name multiple times has no effect. Fails if the name or one of its labels is empty.
+    pub fn register(&mut self, name: &str) -> Result<DynamicScope, ScopeParseError> {
+
+        if !self.scopes_by_name.contains_key(name) {
+            let labels: Labels = split_labels(name, &self.separator)?
+                .into_iter()
+                .map(|label| self.intern(label))
+                .collect();
+
+            self.scopes_by_name.insert(name.to_string(), self.scopes.len());
+            self.scopes.push(labels);
+        }
+
+        self.parse(name)
+    }
+
+    /// Check if a scope name is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.scopes_by_name.contains_key(name)
+    }
+
+    /// The number of registered scopes
+    pub fn len(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Check if no scope is registered
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Iterate over the registered scopes, in registration order
+    pub fn iter(&self) -> impl Iterator<Item = DynamicScope> + '_ {
+        (0..self.scopes.len()).map(|index| self.scope(index))
+    }
+
+    /// Get a registered scope
+    pub fn get(&self, name: &str) -> Option<DynamicScope> {
+        self.scopes_by_name.get(name).map(|index| self.scope(*index))
+    }
+
+    /// Parse a registered scope, failing if the name was not registered
+    pub fn parse(&self, name: &str) -> Result<DynamicScope, ScopeParseError> {
+        self.get(name).ok_or_else(|| ScopeParseError(name.to_string()))
+    }
+
+    /// Make a registered scope include another registered scope, and all the scopes it includes.
+    ///
+    /// Requires the `hierarchy` feature. Fails if one of the scopes is not registered.
+    ///
+    /// ```
+    /// use scopes_rs::{dynamic::ScopeCatalog, hierarchy::Hierarchized};
+    ///
+    /// let mut catalog = ScopeCatalog::new();
+    /// for name in ["admin", "files.read", "users.read"] {
+    ///     catalog.register(name).unwrap();
+    /// }
+    /// catalog.include("admin", "files").unwrap_err();
+    /// catalog.include("admin", "files.read").unwrap();
+    ///
+    /// let admin = catalog.parse("admin").unwrap();
+    /// assert!(admin.includes(&catalog.parse("files.read").unwrap()));
+    /// assert!(!admin.includes(&catalog.parse("users.read").unwrap()));
+    /// ```
+    #[cfg(feature = "hierarchy")]
+    pub fn include(&mut self, scope: &str, included: &str) -> Result<(), ScopeParseError> {
+        let scope = *self.scopes_by_name.get(scope).ok_or_else(|| ScopeParseError(scope.to_string()))?;
+        let included = *self.scopes_by_name.get(included).ok_or_else(|| ScopeParseError(included.to_string()))?;
+
+        let inclusion = (self.scopes[scope].clone(), self.scopes[included].clone());
+        let mut inclusions = self.inclusions.write().unwrap_or_else(PoisonError::into_inner);
+        if !inclusions.contains(&inclusion) {
+            inclusions.push(inclusion);
+        }
+        Ok(())
+    }
+
+    // Create the scope at an index, sharing the inclusions of the catalog
+    fn scope(&self, index: usize) -> DynamicScope {
+
+        #[allow(unused_mut)]
+        let mut scope = DynamicScope::from_labels(self.scopes[index].clone(), self.separator.clone());
+
+        #[cfg(feature = "hierarchy")]
+        {
+            scope.catalog = Some(self.inclusions.clone());
+        }
+
+        scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use crate::dynamic::{DynamicScope, ScopeCatalog};
+
+    #[cfg(feature = "hierarchy")]
+    use crate::hierarchy::Hierarchized;
+
+    #[test]
+    fn test_parse() {
+        let scope = DynamicScope::from_str("files.read").expect("Could not parse scope");
+
+        assert_eq!(vec!["files", "read"], scope.labels().collect::<Vec<_>>());
+        assert_eq!("files.read", scope.to_string());
+        assert_eq!(scope, DynamicScope::new("files.read", ".").unwrap());
+        assert_ne!(scope, DynamicScope::new("files.read", ":").unwrap());
+
+        assert!(DynamicScope::from_str("").is_err());
+        assert!(DynamicScope::from_str("files.").is_err());
+        assert_eq!(vec!["files.read"], DynamicScope::new("files.read", "").unwrap().labels().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_catalog() {
+        let mut catalog = ScopeCatalog::with_separator(":");
+        let read = catalog.register("files:read").expect("Could not register scope");
+        catalog.register("files:write").expect("Could not register scope");
+        catalog.register("files:read").expect("Could not register scope");
+
+        assert!(catalog.register("files::delete").is_err());
+
+        assert_eq!(2, catalog.len());
+        assert!(catalog.contains("files:write"));
+        assert!(!catalog.contains("files"));
+        assert_eq!(Some(read), catalog.get("files:read"));
+        assert_eq!(DynamicScope::new("files:read", ":").unwrap(), catalog.parse("files:read").unwrap());
+        assert!(catalog.parse("files.read").is_err());
+        assert_eq!(vec!["files:read", "files:write"], catalog.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+
+        // Labels are interned
+        let write = catalog.parse("files:write").unwrap();
+        let read = catalog.parse("files:read").unwrap();
+        assert!(Arc::ptr_eq(&write.labels[0], &read.labels[0]));
+    }
+
+    #[cfg(feature = "hierarchy")]
+    #[test]
+    fn test_hierarchy() {
+        let foo = DynamicScope::from_str("foo").unwrap();
+        let foo_bar = DynamicScope::from_str("foo.bar").unwrap();
+        let foobar = DynamicScope::from_str("foobar").unwrap();
+
+        assert!(foo.includes(&foo));
+        assert!(foo.includes(&foo_bar));
+        assert!(!foo_bar.includes(&foo));
+        assert!(!foo.includes(&foobar));
+        assert!(!foo.includes(&DynamicScope::new("foo:bar", ":").unwrap()));
+    }
+
+    #[cfg(feature = "hierarchy")]
+    #[test]
+    fn test_catalog_include() {
+        let mut catalog = ScopeCatalog::new();
+        for name in ["admin", "files", "files.read", "users.read", "users.write", "billing"] {
+            catalog.register(name).unwrap();
+        }
+
+        catalog.include("admin", "files").unwrap();
+        catalog.include("files.read", "users.read").unwrap();
+        catalog.include("users.write", "billing").unwrap();
+
+        assert!(catalog.include("admin", "unknown").is_err());
+
+        let scope = |name: &str| catalog.parse(name).unwrap();
+
+        // Explicit inclusions are transitive, including through label inclusions
+        assert!(scope("admin").includes(&scope("files.read")));
+        assert!(scope("admin").includes(&scope("users.read")));
+        assert!(scope("files").includes(&scope("users.read")));
+        assert!(!scope("admin").includes(&scope("users.write")));
+        assert!(!scope("admin").includes(&scope("billing")));
+        assert!(!scope("files").includes(&scope("admin")));
+
+        // Parsed scopes without a catalog only use labels
+        assert!(!DynamicScope::from_str("admin").unwrap().includes(&scope("files")));
+    }
+
+    #[cfg(feature = "hierarchy")]
+    #[test]
+    fn test_catalog_shared_inclusions() {
+        let mut catalog = ScopeCatalog::new();
+        let admin = catalog.register("admin").unwrap();
+        let files = catalog.register("files").unwrap();
+
+        // Inclusions added after a scope is retrieved apply to it
+        catalog.include("admin", "files").unwrap();
+        assert!(admin.includes(&files));
+        assert_eq!(admin, catalog.parse("admin").unwrap());
+
+        // Scopes parsed without a catalog are equal, but only use their labels
+        let parsed = DynamicScope::from_str("admin").unwrap();
+        assert!(!parsed.includes(&files));
+        assert_eq!(admin, parsed);
+
+        let mut cloned = catalog.clone();
+        cloned.register("users").unwrap();
+        cloned.include("admin", "users").unwrap();
+        assert!(cloned.parse("admin").unwrap().includes(&cloned.parse("users").unwrap()));
+        assert!(!admin.includes(&cloned.parse("users").unwrap()));
+        assert_eq!(admin, cloned.parse("admin").unwrap());
+    }
+}
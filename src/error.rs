@@ -14,3 +14,50 @@ impl Display for ScopeParseError {
 }
 
 impl Error for ScopeParseError {}
+
+/// An error in a set of requested scopes, returned by the `validate_request()` function
+/// generated by the derive macro
+#[derive(Debug, PartialEq)]
+pub enum ScopeRequestError<'a, S> {
+
+    /// A requested scope requires another scope that was not requested
+    MissingRequirement {
+        /// The requested scope
+        scope: &'a S,
+        /// The missing scope
+        required: S,
+    },
+
+    /// Two requested scopes can't be requested together
+    Conflict {
+        /// The requested scope
+        scope: &'a S,
+        /// The scope conflicting with `scope`
+        conflicting: &'a S,
+    },
+}
+
+impl<S: Display> Display for ScopeRequestError<'_, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScopeRequestError::MissingRequirement { scope, required } => write!(f, "scope '{}' requires scope '{}'", scope, required),
+            ScopeRequestError::Conflict { scope, conflicting } => write!(f, "scope '{}' conflicts with scope '{}'", scope, conflicting),
+        }
+    }
+}
+
+impl<S: Display + std::fmt::Debug> Error for ScopeRequestError<'_, S> {}
+
+/// The error returned when converting a [`Policy`](crate::policy::Policy) to another scope type
+/// fails, with the names of the scopes that could not be converted
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyConversionError(pub Vec<String>);
+
+impl Display for PolicyConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<String> = self.0.iter().map(|name| format!("'{}'", name)).collect();
+        write!(f, "unknown scopes: {}", names.join(", "))
+    }
+}
+
+impl Error for PolicyConversionError {}
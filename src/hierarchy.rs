@@ -1,4 +1,4 @@
-//! Contains the required trait for scope hierarchy
+//! Contains the required trait for scope hierarchy, and functions to expand or reduce sets of scopes
 //! 
 //! Requires the `hierarchy` feature.
 
@@ -65,3 +65,154 @@ pub trait Hierarchized {
         other.includes(self)
     }
 }
+
+/// Add every scope included by the given scopes.
+/// 
+/// Since the included scopes can't be enumerated from a [`Hierarchized`] implementation, they are taken
+/// from `known`, like the `ALL` constant generated by the derive macro or the scopes of a
+/// [`ScopeCatalog`](crate::dynamic::ScopeCatalog). The given scopes are kept first and in order, without
+/// duplicates, followed by the included scopes in the order of `known`.
+/// 
+/// This is useful to make checks based on the exact scopes, like a resource server comparing scope names,
+/// accept the scopes included in the granted ones.
+/// 
+/// ```
+/// use scopes_rs::{derive::Scope, hierarchy::expand};
+/// 
+/// #[derive(Clone, Debug, PartialEq, Scope)]
+/// enum MyScope {
+///     Contacts,
+///     ContactsReadonly,
+///     Calendar,
+/// }
+/// 
+/// let scopes = expand([MyScope::Contacts], MyScope::ALL);
+/// assert_eq!(scopes, vec![MyScope::Contacts, MyScope::ContactsReadonly]);
+/// ```
+pub fn expand<'a, S>(scopes: impl IntoIterator<Item = S>, known: impl IntoIterator<Item = &'a S>) -> Vec<S>
+where
+    S: Hierarchized + PartialEq + Clone + 'a,
+{
+    let mut expanded: Vec<S> = Vec::new();
+
+    for scope in scopes {
+        if !expanded.contains(&scope) {
+            expanded.push(scope);
+        }
+    }
+
+    // Inclusion is transitive, so the scopes included in an added scope are already included in the given scopes
+    let granted = expanded.len();
+    for scope in known {
+        if !expanded.contains(scope) && expanded[..granted].iter().any(|s| s.includes(scope)) {
+            expanded.push(scope.clone());
+        }
+    }
+
+    expanded
+}
+
+/// Remove the scopes included by another one of the given scopes.
+/// 
+/// The result grants the same scopes as the given ones, with as few scopes as possible, which keeps
+/// the issued tokens short. When several scopes include each other, only the first one is kept.
+/// The remaining scopes keep their relative order.
+/// 
+/// ```
+/// use scopes_rs::{derive::Scope, hierarchy::reduce};
+/// 
+/// #[derive(Clone, Debug, PartialEq, Scope)]
+/// enum MyScope {
+///     Contacts,
+///     ContactsReadonly,
+///     Calendar,
+/// }
+/// 
+/// let scopes = reduce([MyScope::ContactsReadonly, MyScope::Calendar, MyScope::Contacts]);
+/// assert_eq!(scopes, vec![MyScope::Calendar, MyScope::Contacts]);
+/// ```
+pub fn reduce<S: Hierarchized>(scopes: impl IntoIterator<Item = S>) -> Vec<S> {
+    let mut reduced: Vec<S> = Vec::new();
+
+    for scope in scopes {
+        if reduced.iter().any(|s| s.includes(&scope)) {
+            continue;
+        }
+
+        reduced.retain(|s| !scope.includes(s));
+        reduced.push(scope);
+    }
+
+    reduced
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{derive::Scope, dynamic::ScopeCatalog, hierarchy::{expand, reduce}};
+
+    #[derive(Clone, Debug, PartialEq, Scope)]
+    #[scope(allow_cycles)]
+    enum MyScope {
+        Files,
+        FilesRead,
+        FilesWrite,
+        #[scope(include = Legacy)]
+        Admin,
+        #[scope(include = Admin)]
+        Legacy,
+        #[scope(rename = "repo.{0}")]
+        Repo(u32),
+        #[scope(rename = "repo.{0}.read")]
+        RepoRead(u32),
+        #[scope(other)]
+        Unknown(String),
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand([MyScope::FilesWrite, MyScope::Files, MyScope::FilesWrite], MyScope::ALL),
+            vec![MyScope::FilesWrite, MyScope::Files, MyScope::FilesRead],
+        );
+        assert_eq!(expand([MyScope::Admin], MyScope::ALL), vec![MyScope::Admin, MyScope::Legacy]);
+        assert_eq!(expand(Vec::<MyScope>::new(), MyScope::ALL), vec![]);
+
+        // Parameterized scopes are only added if they are known
+        let known = [MyScope::RepoRead(1), MyScope::RepoRead(2)];
+        assert_eq!(expand([MyScope::Repo(1)], &known), vec![MyScope::Repo(1), MyScope::RepoRead(1)]);
+
+        let unknown = MyScope::Unknown("foo".to_string());
+        assert_eq!(expand([unknown.clone()], MyScope::ALL), vec![unknown]);
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(
+            reduce([MyScope::FilesRead, MyScope::Admin, MyScope::Files, MyScope::FilesWrite]),
+            vec![MyScope::Admin, MyScope::Files],
+        );
+        assert_eq!(reduce([MyScope::Legacy, MyScope::Admin, MyScope::Legacy]), vec![MyScope::Legacy]);
+        assert_eq!(
+            reduce([MyScope::RepoRead(1), MyScope::RepoRead(2), MyScope::Repo(1)]),
+            vec![MyScope::RepoRead(2), MyScope::Repo(1)],
+        );
+    }
+
+    #[test]
+    fn test_dynamic_scopes() {
+        let mut catalog = ScopeCatalog::new();
+        for name in ["files", "files.read", "files.write", "admin"] {
+            catalog.register(name).expect("Could not register scope");
+        }
+        catalog.include("admin", "files.read").expect("Could not include scope");
+
+        let admin = catalog.parse("admin").unwrap();
+        let known: Vec<_> = catalog.iter().collect();
+
+        let expanded: Vec<String> = expand([admin], &known).iter().map(ToString::to_string).collect();
+        assert_eq!(expanded, ["admin", "files.read"]);
+
+        let reduced: Vec<String> = reduce(catalog.iter()).iter().map(ToString::to_string).collect();
+        assert_eq!(reduced, ["files", "admin"]);
+    }
+}
@@ -6,15 +6,30 @@
 //! - `hierarchy`: Enable hierarchical scopes support. See [`hierarchy`] for more details.
 //! 
 
+// Allow the derive macro to be used in this crate
+extern crate self as scopes_rs;
+
 pub mod scope;
 
 pub mod error;
 
+pub mod metadata;
+
+pub mod dynamic;
+
 #[cfg(feature = "hierarchy")]
 pub mod hierarchy;
 
 pub mod policy;
 
+pub mod wildcard;
+
+pub mod list;
+
+pub mod oidc;
+
+pub mod negotiation;
+
 /// Derive macro for the [`Scope`](scope::Scope) trait
 pub mod derive {
 
@@ -51,6 +66,15 @@ pub mod derive {
     /// | `foo_bar` | `foo_bar` |
     /// | `BAZ` | `b.a.z` |
     /// 
+    /// With the `split = "acronyms"` enum attribute, consecutive uppercase letters and digits are kept
+    /// in the same label instead :
+    /// 
+    /// | Variant name | Inferred name |
+    /// |--------------|---------------|
+    /// | `BAZ` | `baz` |
+    /// | `HTTPRequest` | `http.request` |
+    /// | `HttpV2Access` | `http.v2.access` |
+    /// 
     /// Labels do not convoy any special meaning, but are used to infer the scopes hierarchy 
     /// (when the `hierarchy` feature is enabled). A scope will include another one if its
     /// labels are a prefix of the other one. For example, `foo.bar` includes `foo.bar.baz` but
@@ -58,6 +82,329 @@ pub mod derive {
     /// 
     ///  When using the `rename` attribute, the labels will be parsed by splitting the name by `separator`.
     /// 
+    /// ## Naming conventions
+    /// 
+    /// The `rename_all` enum attribute changes how the labels are converted and joined. It can't
+    /// be used with the `separator` attribute.
+    /// 
+    /// | `rename_all` | `FooBar` becomes |
+    /// |--------------|------------------|
+    /// | `"dot.case"` (default) | `foo.bar` |
+    /// | `"snake_case"` | `foo_bar` |
+    /// | `"kebab-case"` | `foo-bar` |
+    /// | `"colon:case"` | `foo:bar` |
+    /// | `"SCREAMING_SNAKE"` | `FOO_BAR` |
+    /// | `"verbatim"` | `FooBar` |
+    /// 
+    /// The labels are still split at each uppercase letter for the hierarchy, so `Foo` includes `FooBar`
+    /// with every convention. With `"verbatim"`, a renamed scope has a single label.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Debug, PartialEq, Scope)]
+    /// #[scope(rename_all = "kebab-case")]
+    /// enum MyScope {
+    ///     ContactsReadonly,
+    /// }
+    /// 
+    /// assert_eq!(MyScope::from_str("contacts-readonly").unwrap(), MyScope::ContactsReadonly);
+    /// ```
+    /// 
+    /// # Aliases
+    /// 
+    /// Additional names can be accepted when parsing a scope with the `alias` attribute, which
+    /// can be repeated. The prefix is also added to aliases. The canonical name is still used
+    /// by `scope_name()` and the [`Display`](std::fmt::Display) implementation generated with
+    /// `#[scope(display)]`.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Debug, PartialEq, Scope)]
+    /// #[scope(display)]
+    /// enum MyScope {
+    ///     #[scope(alias = "contacts.read", alias = "contacts_ro")]
+    ///     ContactsReadonly,
+    /// }
+    /// 
+    /// assert_eq!(MyScope::from_str("contacts.read").unwrap(), MyScope::ContactsReadonly);
+    /// assert_eq!(MyScope::from_str("contacts_ro").unwrap(), MyScope::ContactsReadonly);
+    /// assert_eq!(MyScope::ContactsReadonly.to_string(), "contacts.readonly");
+    /// ```
+    /// 
+    /// # Case-insensitive parsing
+    /// 
+    /// With the `case_insensitive` enum attribute, the generated [`FromStr`](std::str::FromStr)
+    /// implementation ignores the ASCII case and the surrounding whitespace of the input. 
+    /// 
+    /// A `normalize` function can also be given to transform the input before parsing it. It
+    /// takes a `&str` and returns anything implementing `AsRef<str>`.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// fn strip_legacy_prefix(s: &str) -> &str {
+    ///     s.strip_prefix("legacy:").unwrap_or(s)
+    /// }
+    /// 
+    /// #[derive(Debug, PartialEq, Scope)]
+    /// #[scope(case_insensitive, normalize = strip_legacy_prefix)]
+    /// enum MyScope {
+    ///     ProfileReadonly,
+    /// }
+    /// 
+    /// assert_eq!(MyScope::from_str(" Profile.ReadOnly ").unwrap(), MyScope::ProfileReadonly);
+    /// assert_eq!(MyScope::from_str("legacy:PROFILE.READONLY").unwrap(), MyScope::ProfileReadonly);
+    /// ```
+    /// 
+    /// # Parameterized scopes
+    /// 
+    /// Tuple and struct variants are supported with a name template given with `rename`. Each label
+    /// of the template can be a parameter like `{0}` for tuple variants or `{field}` for struct variants,
+    /// and every field must be used exactly once. Fields must implement [`FromStr`](std::str::FromStr)
+    /// and [`Display`](std::fmt::Display).
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(separator = ":", display)]
+    /// enum MyScope {
+    ///     #[scope(rename = "repo:{0}:read")]
+    ///     RepoRead(String),
+    ///     #[scope(rename = "org:{slug}:admin")]
+    ///     OrgAdmin { slug: String },
+    /// }
+    /// 
+    /// assert_eq!(MyScope::from_str("repo:scopes-rs:read").unwrap(), MyScope::RepoRead("scopes-rs".to_string()));
+    /// assert_eq!(MyScope::OrgAdmin { slug: "acme".to_string() }.to_string(), "org:acme:admin");
+    /// ```
+    /// 
+    /// Names without parameters are matched first when parsing, then templates are tried in declaration
+    /// order. Parameters can't be empty, and must be parsed successfully by the field type. A parsed value
+    /// must also be displayed as a single label, without the separator.
+    /// 
+    /// `Display` is only the inverse of parsing for values displayed as a single label : a value containing
+    /// the separator, like `RepoRead("a:b".to_string())`, is displayed as `repo:a:b:read`, which can't be
+    /// parsed back to the same scope.
+    /// 
+    /// In the hierarchy, a parameter label is included by a parameter label at the same position with an
+    /// equal value, so `repo:{0}` includes `repo:{0}:read` for the same repository. Scopes included with
+    /// `include` or `parent` must have equal values for the parameters with the same name, which must have the
+    /// same type. When the including scope has parameters, every parameter of the included scope must correspond
+    /// to one of them, unless the attribute is used with `allow_unbound_params` to include every value of
+    /// the other parameters.
+    /// 
+    /// # Internal scopes
+    /// 
+    /// Variants marked with `#[scope(internal)]` or `#[scope(skip_parse)]` are never parsed from a string,
+    /// so they can only be created by your own code. They can still be used in policies and in the hierarchy.
+    /// 
+    /// The generated `scopes_supported()` function lists the names of the scopes that can be parsed, without
+    /// internal and parameterized scopes, and `is_internal()` checks if a scope is internal.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     Profile,
+    ///     #[scope(internal)]
+    ///     ServiceSync,
+    /// }
+    /// 
+    /// assert!(MyScope::from_str("service.sync").is_err());
+    /// assert_eq!(MyScope::scopes_supported(), ["profile"]);
+    /// assert!(MyScope::ServiceSync.is_internal());
+    /// ```
+    /// 
+    /// # Default scopes
+    /// 
+    /// Variants marked with `#[scope(default)]` are listed by the generated `defaults()` function, and the enum
+    /// implements [`DefaultScopes`](crate::list::DefaultScopes).
+    /// [`ScopeList::parse_or_default`](crate::list::ScopeList::parse_or_default) uses them when no scope is requested,
+    /// and replaces the default groups of scopes with their members.
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(default)]
+    ///     Profile,
+    ///     Email,
+    /// }
+    /// 
+    /// assert_eq!(MyScope::defaults(), [MyScope::Profile]);
+    /// ```
+    /// 
+    /// # Requirements and conflicts
+    /// 
+    /// The `requires` and `conflicts_with` variant attributes take a scope variant or a list of variants, like `include`.
+    /// The generated `validate_request()` function checks that a set of requested scopes contains the scopes
+    /// required by each scope, and no conflicting scopes. It returns every [`ScopeRequestError`](crate::error::ScopeRequestError)
+    /// found in the request.
+    /// 
+    /// With the `hierarchy` feature, a required scope is also present if a requested scope includes it.
+    /// Conflicts only apply to the given variants, and are symmetric.
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// use scopes_rs::error::ScopeRequestError;
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(rename_all = "snake_case")]
+    /// enum MyScope {
+    ///     Openid,
+    ///     #[scope(requires = Openid)]
+    ///     OfflineAccess,
+    ///     #[scope(conflicts_with = OfflineAccess)]
+    ///     SingleUse,
+    /// }
+    /// 
+    /// assert!(MyScope::validate_request(&[MyScope::Openid, MyScope::OfflineAccess]).is_ok());
+    /// 
+    /// let request = [MyScope::OfflineAccess];
+    /// assert_eq!(
+    ///     MyScope::validate_request(&request),
+    ///     Err(vec![ScopeRequestError::MissingRequirement { scope: &request[0], required: MyScope::Openid }]),
+    /// );
+    /// ```
+    /// 
+    /// # Groups of scopes
+    /// 
+    /// A variant marked with `#[scope(expands_to = ...)]` is a group of scopes, like `include`. When parsing
+    /// a [`ScopeList`](crate::list::ScopeList), a group is replaced by its members, so the list only contains
+    /// concrete scopes. Groups can contain other groups, which are expanded recursively, but a group cannot
+    /// expand to itself. Internal scopes can only be members of internal groups, so parsing a group never
+    /// gives a scope that can't be parsed.
+    /// 
+    /// Unlike `include`, groups don't change the hierarchy : a group only grants what it expands to.
+    /// The members of a group are available with [`Scope::expansion`](crate::scope::Scope::expansion).
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// use scopes_rs::list::ScopeList;
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(rename_all = "snake_case")]
+    /// enum MyScope {
+    ///     Openid,
+    ///     Profile,
+    ///     Email,
+    ///     #[scope(expands_to = [Openid, Profile, Email])]
+    ///     Basic,
+    /// }
+    /// 
+    /// let scopes: ScopeList<MyScope> = "basic email".parse().unwrap();
+    /// assert_eq!(scopes.as_slice(), &[MyScope::Openid, MyScope::Profile, MyScope::Email]);
+    /// ```
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     Profile,
+    ///     #[scope(internal)]
+    ///     ServiceSync,
+    ///     #[scope(expands_to = [Profile, ServiceSync])]
+    ///     Basic,
+    /// }
+    /// ```
+    /// 
+    /// # Unknown scopes
+    /// 
+    /// A variant with a single field can be marked with `#[scope(other)]` to store the scopes that
+    /// don't match any other variant, so parsing never fails. The original input is stored in the field,
+    /// and written as is by `Display`.
+    /// 
+    /// This variant is a catch-all scope : it is never matched by a [`Policy`](crate::policy::Policy),
+    /// doesn't include other scopes, and is not in `ALL`.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// use scopes_rs::policy::IntoPolicy;
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     Foo,
+    ///     #[scope(other)]
+    ///     Unknown(String),
+    /// }
+    /// 
+    /// let scope = MyScope::from_str("billing.read").unwrap();
+    /// assert_eq!(scope, MyScope::Unknown("billing.read".to_string()));
+    /// assert!(!scope.clone().into_policy().verify(&[scope]));
+    /// ```
+    /// 
+    /// # Nested scopes
+    /// 
+    /// A variant marked with `#[scope(nested)]` wraps the scopes of another scope enum, so large APIs can
+    /// split their scopes in several enums. The names of the wrapped scopes are prefixed with the labels of the
+    /// variant, and parsing, `Display`, wildcards, groups and the hierarchy are delegated to the wrapped enum.
+    /// The wrapped enums must implement `Display` when the `display` option is used.
+    /// 
+    /// Nested scopes are not listed in `ALL`, but `scopes_supported()` and `defaults()` list the scopes of the
+    /// wrapped enums with the prefix of their variant. With the `scope_name_getter` option, the name of a nested
+    /// scope is built from the `scope_name()` of the wrapped scope, so the wrapped enums must also use this option.
+    /// 
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(rename_all = "snake_case", display)]
+    /// enum BillingScope {
+    ///     Invoices,
+    ///     InvoicesRead,
+    /// }
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(display)]
+    /// enum AppScope {
+    ///     Profile,
+    ///     #[scope(nested)]
+    ///     Billing(BillingScope),
+    /// }
+    /// 
+    /// let scope = AppScope::from_str("billing.invoices_read").unwrap();
+    /// assert_eq!(scope, AppScope::Billing(BillingScope::InvoicesRead));
+    /// assert_eq!(scope.to_string(), "billing.invoices_read");
+    /// assert_eq!(AppScope::scopes_supported(), ["profile", "billing.invoices", "billing.invoices_read"]);
+    /// ```
+    /// 
+    /// # Enumerating scopes
+    /// 
+    /// The derive macro also generates the following items on the enum, with the same visibility
+    /// as the enum itself :
+    /// 
+    /// - `const ALL: &'static [Self]`: every variant without parameters, in declaration order
+    /// - `const COUNT: usize`: the number of variants without parameters, which is the length of `ALL`
+    /// - `const fn index(&self) -> usize`: the index of a variant in `ALL`
+    /// - `const fn from_index(index: usize) -> Option<Self>`: the variant at an index in `ALL`
+    /// 
+    /// The variants with fields, like parameterized, nested and catch-all variants, are not in `ALL`. They are
+    /// indexed after the variants of `ALL`, from `COUNT`, in declaration order, so every variant has an entry
+    /// in the `METADATA` table.
+    /// 
+    /// The [`ScopeMetadata`](crate::metadata::ScopeMetadata) of each variant is available with
+    /// `metadata()` and in the `METADATA` table, and its description with `description()`.
+    /// See the [`metadata`](crate::metadata) module for more details.
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     Foo,
+    ///     Bar,
+    /// }
+    /// 
+    /// assert_eq!(MyScope::ALL, &[MyScope::Foo, MyScope::Bar]);
+    /// assert_eq!(MyScope::COUNT, 2);
+    /// assert_eq!(MyScope::Bar.index(), 1);
+    /// assert_eq!(MyScope::from_index(0), Some(MyScope::Foo));
+    /// ```
+    /// 
     /// # Errors
     /// 
     /// Compilation will fail if scopes have conflicting names :
@@ -71,6 +418,103 @@ pub mod derive {
     /// }
     /// ```
     /// 
+    /// This includes aliases :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     Foo,
+    ///     #[scope(alias = "foo")]
+    ///     Bar,
+    /// }
+    /// ```
+    /// 
+    /// and names that only differ by their case when parsing is case-insensitive :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// #[scope(case_insensitive)]
+    /// enum MyScope {
+    ///     Foo,
+    ///     #[scope(rename = "FOO")]
+    ///     Bar,
+    /// }
+    /// ```
+    /// 
+    /// or templates that only differ by their parameter names :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(rename = "repo.{0}")]
+    ///     Repo(String),
+    ///     #[scope(rename = "repo.{id}")]
+    ///     RepoId { id: String },
+    /// }
+    /// ```
+    /// 
+    /// Required and conflicting scopes must be variants of the enum :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(requires = Bar)]
+    ///     Foo,
+    /// }
+    /// ```
+    /// 
+    /// Scope names must be valid OAuth 2.0 scope tokens, as defined in
+    /// [RFC 6749 section 3.3](https://www.rfc-editor.org/rfc/rfc6749#section-3.3), unless the enum is marked
+    /// with `#[scope(validate_names = false)]` :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(rename = "read files")]
+    ///     Foo,
+    /// }
+    /// ```
+    /// 
+    /// Scope names and their labels cannot be empty :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(rename = "files..read")]
+    ///     Foo,
+    /// }
+    /// ```
+    /// 
+    /// Groups cannot expand to themselves, even through other groups :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(expands_to = Bar)]
+    ///     Foo,
+    ///     #[scope(expands_to = Foo)]
+    ///     Bar,
+    /// }
+    /// ```
+    /// 
+    /// Aliases can't be used on internal scopes :
+    /// 
+    /// ```compile_fail
+    /// # use scopes_rs::derive::Scope;
+    /// #[derive(Clone, PartialEq, Scope)]
+    /// enum MyScope {
+    ///     #[scope(internal, alias = "bar")]
+    ///     Foo,
+    /// }
+    /// ```
+    /// 
     /// # Optional attributes 
     /// 
     /// [`Scope`]: ../scope/Scope
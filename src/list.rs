@@ -0,0 +1,191 @@
+//! Lists of scopes, like the `scope` parameter of OAuth 2.0
+//!
+//! A [`ScopeList<S>`] is parsed from and displayed as space-delimited scope names,
+//! as described in [RFC 6749 section 3.3](https://www.rfc-editor.org/rfc/rfc6749#section-3.3).
+//!
+//! ```
+//! # use scopes_rs::derive::Scope;
+//! use scopes_rs::{list::ScopeList, policy::IntoPolicy};
+//!
+//! #[derive(Clone, Debug, PartialEq, Scope)]
+//! #[scope(display)]
+//! enum MyScope {
+//!     #[scope(default)]
+//!     Profile,
+//!     Email,
+//! }
+//!
+//! let scopes: ScopeList<MyScope> = "profile email".parse().unwrap();
+//! assert_eq!(scopes.as_slice(), &[MyScope::Profile, MyScope::Email]);
+//! assert_eq!(scopes.to_string(), "profile email");
+//! assert!(MyScope::Email.into_policy().verify(&scopes));
+//!
+//! // Empty requests get the default scopes
+//! let scopes = ScopeList::<MyScope>::parse_or_default("").unwrap();
+//! assert_eq!(scopes.as_slice(), &[MyScope::Profile]);
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::scope::Scope;
+
+/// A scope type with a set of default scopes
+///
+/// This is implemented by the derive macro, with the variants marked with `#[scope(default)]`.
+pub trait DefaultScopes: Scope + Sized + 'static {
+
+    /// The scopes granted when no scope is requested
+    fn defaults() -> Vec<Self>;
+}
+
+/// A list of scopes, parsed from space-delimited scope names
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeList<S: Scope>(Vec<S>);
+
+impl<S: Scope> ScopeList<S> {
+
+    /// Create an empty list
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Parse a list of space-delimited scopes, or get the default scopes if the input is empty
+    /// or only contains whitespace.
+    /// 
+    /// Groups of scopes are replaced by their members, including in the default scopes.
+    pub fn parse_or_default(s: &str) -> Result<Self, S::Err>
+    where
+        S: DefaultScopes,
+    {
+        if s.trim().is_empty() {
+            let mut scopes = Self::new();
+            for scope in S::defaults() {
+                scopes.push_expanded(scope);
+            }
+            return Ok(scopes);
+        }
+        s.parse()
+    }
+
+    // Add a scope to the list, replacing groups with their members and skipping duplicates
+    fn push_expanded(&mut self, scope: S) {
+        let expanded = match scope.expansion() {
+            Some(members) => members,
+            None => vec![scope],
+        };
+
+        for scope in expanded {
+            if !self.0.contains(&scope) {
+                self.0.push(scope);
+            }
+        }
+    }
+
+    /// The scopes of this list
+    pub fn as_slice(&self) -> &[S] {
+        &self.0
+    }
+
+    /// Iterate over the scopes of this list
+    pub fn iter(&self) -> std::slice::Iter<'_, S> {
+        self.0.iter()
+    }
+
+    /// The number of scopes in this list
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if this list is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the scopes of this list
+    pub fn into_inner(self) -> Vec<S> {
+        self.0
+    }
+}
+
+impl<S: Scope> Default for ScopeList<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Scope> FromStr for ScopeList<S> {
+    type Err = S::Err;
+
+    /// Parse a list of space-delimited scopes.
+    /// 
+    /// Groups of scopes are replaced by their members, and duplicate scopes are removed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scopes = Self::new();
+
+        for name in s.split_ascii_whitespace() {
+            scopes.push_expanded(S::from_str(name)?);
+        }
+
+        Ok(scopes)
+    }
+}
+
+impl<S: Scope + Display> Display for ScopeList<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, scope) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            scope.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Scope> From<Vec<S>> for ScopeList<S> {
+    fn from(value: Vec<S>) -> Self {
+        Self(value)
+    }
+}
+
+impl<S: Scope> FromIterator<S> for ScopeList<S> {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<S: Scope> IntoIterator for ScopeList<S> {
+    type Item = S;
+    type IntoIter = std::vec::IntoIter<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, S: Scope> IntoIterator for &'a ScopeList<S> {
+    type Item = &'a S;
+    type IntoIter = std::slice::Iter<'a, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::list::ScopeList;
+
+    #[test]
+    fn test_parse_list() {
+        let list: ScopeList<String> = " foo  bar\tbaz ".parse().unwrap();
+        assert_eq!(list.as_slice(), &["foo", "bar", "baz"]);
+        assert_eq!(list.to_string(), "foo bar baz");
+
+        let list: ScopeList<String> = "".parse().unwrap();
+        assert!(list.is_empty());
+
+        let list: ScopeList<String> = "foo bar foo".parse().unwrap();
+        assert_eq!(list.as_slice(), &["foo", "bar"]);
+    }
+}
@@ -0,0 +1,47 @@
+//! Human-readable metadata attached to scopes
+//! 
+//! The derive macro generates a [`ScopeMetadata`] for every scope variant, which can be
+//! used to build OAuth consent screens or API documentation.
+//! 
+//! ```
+//! use scopes_rs::derive::Scope;
+//! 
+//! #[derive(PartialEq, Scope)]
+//! enum MyScope {
+//!     /// Read your profile
+//!     ProfileReadonly,
+//! 
+//!     #[scope(description = "Manage your contacts", title = "Contacts", category = "Social", icon = "contacts")]
+//!     Contacts,
+//! }
+//! 
+//! assert_eq!(MyScope::ProfileReadonly.description(), Some("Read your profile"));
+//! 
+//! let metadata = MyScope::Contacts.metadata();
+//! assert_eq!(metadata.name, "contacts");
+//! assert_eq!(metadata.title, Some("Contacts"));
+//! assert_eq!(metadata.category, Some("Social"));
+//! assert_eq!(metadata.icon, Some("contacts"));
+//! ```
+
+/// Metadata of a scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeMetadata {
+
+    /// The full name of the scope
+    pub name: &'static str,
+
+    /// A human-readable description of the scope
+    /// 
+    /// This is taken from the `description` attribute, or from the doc comment of the variant.
+    pub description: Option<&'static str>,
+
+    /// A short human-readable title for the scope
+    pub title: Option<&'static str>,
+
+    /// A category used to group related scopes
+    pub category: Option<&'static str>,
+
+    /// An icon associated to the scope
+    pub icon: Option<&'static str>,
+}
@@ -0,0 +1,284 @@
+//! Scope negotiation, to decide which requested scopes are granted
+//!
+//! When a client requests scopes, an authorization server grants the requested scopes that the client
+//! is allowed to request and that the user consented to. A [`Negotiation`] checks the requested scopes
+//! against these limits, and returns the granted scopes with the dropped scopes and the reason they
+//! were dropped.
+//!
+//! With the `hierarchy` feature, a requested scope is within a limit if a scope of the limit includes it,
+//! so a request for `contacts.readonly` is granted by an allowance of `contacts`. Negotiation never narrows
+//! a requested scope : a request for `contacts` with an allowance of `contacts.readonly` drops `contacts`
+//! and doesn't grant `contacts.readonly` instead, since only requested scopes are granted. Catch-all scopes
+//! are never granted.
+//!
+//! ```
+//! use scopes_rs::{derive::Scope, negotiation::{DropReason, Negotiation}};
+//!
+//! #[derive(Clone, Debug, PartialEq, Scope)]
+//! #[scope(rename_all = "snake_case")]
+//! enum MyScope {
+//!     Profile,
+//!     Email,
+//!     FilesRead,
+//! }
+//!
+//! let allowed = [MyScope::Profile, MyScope::Email];
+//! let consented = [MyScope::Profile];
+//!
+//! let negotiated = Negotiation::new()
+//!     .allowed(&allowed)
+//!     .consented(&consented)
+//!     .negotiate([MyScope::Profile, MyScope::Email, MyScope::FilesRead]);
+//!
+//! assert_eq!(negotiated.granted, vec![MyScope::Profile]);
+//! assert_eq!(negotiated.dropped, vec![
+//!     (MyScope::Email, DropReason::NotConsented),
+//!     (MyScope::FilesRead, DropReason::NotAllowed),
+//! ]);
+//! ```
+//!
+//! # Token exchange
+//!
+//! With an [RFC 8693](https://www.rfc-editor.org/rfc/rfc8693) token exchange, the issued token can't have
+//! more scopes than the subject token. [`downscope`] grants the requested scopes within the scopes of the
+//! subject token.
+//!
+//! ```
+//! # use scopes_rs::derive::Scope;
+//! use scopes_rs::negotiation::{DropReason, downscope};
+//!
+//! # #[derive(Clone, Debug, PartialEq, Scope)]
+//! # enum MyScope { FilesRead, FilesWrite }
+//! let negotiated = downscope([MyScope::FilesRead, MyScope::FilesWrite], &[MyScope::FilesRead]);
+//!
+//! assert_eq!(negotiated.granted, vec![MyScope::FilesRead]);
+//! assert_eq!(negotiated.dropped, vec![(MyScope::FilesWrite, DropReason::NotInSubjectToken)]);
+//! ```
+
+use std::fmt::Display;
+
+use crate::scope::Scope;
+
+/// The reason a requested scope was not granted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropReason {
+
+    /// The scope is a catch-all scope, which is never granted
+    Unknown,
+
+    /// The client is not allowed to request the scope
+    NotAllowed,
+
+    /// The user did not consent to the scope
+    NotConsented,
+
+    /// The scope is not in the subject token of a token exchange
+    NotInSubjectToken,
+}
+
+impl Display for DropReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DropReason::Unknown => write!(f, "unknown scope"),
+            DropReason::NotAllowed => write!(f, "scope not allowed for the client"),
+            DropReason::NotConsented => write!(f, "scope not consented by the user"),
+            DropReason::NotInSubjectToken => write!(f, "scope not in the subject token"),
+        }
+    }
+}
+
+/// The result of a [`Negotiation`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Negotiated<S> {
+
+    /// The granted scopes, in the requested order and without duplicates
+    pub granted: Vec<S>,
+
+    /// The scopes that were requested but not granted, with the reason they were dropped
+    pub dropped: Vec<(S, DropReason)>,
+}
+
+impl<S> Negotiated<S> {
+
+    /// Check if every requested scope was granted
+    pub fn is_complete(&self) -> bool {
+        self.dropped.is_empty()
+    }
+}
+
+/// The limits of the scopes that can be granted
+///
+/// A requested scope is granted if it is within every limit. Otherwise, it is dropped with the reason
+/// of the first limit it is not within, in the order the limits were added.
+#[derive(Debug, Clone)]
+pub struct Negotiation<'a, S: Scope> {
+    limits: Vec<(&'a [S], DropReason)>,
+}
+
+impl<'a, S: Scope> Negotiation<'a, S> {
+
+    /// Create a negotiation without limits, which grants every requested scope except catch-all scopes
+    pub fn new() -> Self {
+        Self { limits: Vec::new() }
+    }
+
+    /// Only grant the scopes the client is allowed to request
+    pub fn allowed(self, scopes: &'a [S]) -> Self {
+        self.limit(scopes, DropReason::NotAllowed)
+    }
+
+    /// Only grant the scopes the user consented to
+    pub fn consented(self, scopes: &'a [S]) -> Self {
+        self.limit(scopes, DropReason::NotConsented)
+    }
+
+    /// Only grant the scopes of the subject token of a token exchange
+    pub fn subject_token(self, scopes: &'a [S]) -> Self {
+        self.limit(scopes, DropReason::NotInSubjectToken)
+    }
+
+    /// Only grant the given scopes, and drop the other scopes with the given reason
+    pub fn limit(mut self, scopes: &'a [S], reason: DropReason) -> Self {
+        self.limits.push((scopes, reason));
+        self
+    }
+
+    /// Get the requested scopes that can be granted.
+    ///
+    /// A scope requested several times is only granted or dropped once, at its first position. Requested
+    /// scopes are never replaced by the narrower scopes of the limits they include.
+    pub fn negotiate(&self, requested: impl IntoIterator<Item = S>) -> Negotiated<S> {
+        let mut negotiated = Negotiated { granted: Vec::new(), dropped: Vec::new() };
+
+        for scope in requested {
+            if negotiated.granted.contains(&scope) || negotiated.dropped.iter().any(|(dropped, _)| dropped == &scope) {
+                continue;
+            }
+
+            let reason = if scope.is_catch_all() {
+                Some(DropReason::Unknown)
+            } else {
+                self.limits.iter().find(|(limit, _)| !within(&scope, limit)).map(|(_, reason)| *reason)
+            };
+
+            match reason {
+                Some(reason) => negotiated.dropped.push((scope, reason)),
+                None => negotiated.granted.push(scope),
+            }
+        }
+
+        negotiated
+    }
+}
+
+impl<S: Scope> Default for Negotiation<'_, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Grant the requested scopes within the scopes of the subject token of an RFC 8693 token exchange
+pub fn downscope<S: Scope>(requested: impl IntoIterator<Item = S>, subject_scopes: &[S]) -> Negotiated<S> {
+    Negotiation::new().subject_token(subject_scopes).negotiate(requested)
+}
+
+// Check if a scope is one of the scopes of a limit, or included in one of them with the `hierarchy` feature
+fn within<S: Scope>(scope: &S, limit: &[S]) -> bool {
+    #[cfg(not(feature = "hierarchy"))]
+    return limit.iter().any(|s| !s.is_catch_all() && s == scope);
+
+    #[cfg(feature = "hierarchy")]
+    return limit.iter().any(|s| !s.is_catch_all() && s.includes(scope));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{derive::Scope, negotiation::{DropReason, Negotiated, Negotiation, downscope}};
+
+    #[derive(Clone, Debug, PartialEq, Scope)]
+    enum MyScope {
+        Contacts,
+        ContactsReadonly,
+        Calendar,
+        #[scope(other)]
+        Unknown(String),
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let allowed = [MyScope::Contacts, MyScope::ContactsReadonly, MyScope::Unknown("calendar".to_string())];
+        let consented = [MyScope::ContactsReadonly, MyScope::Calendar];
+        let negotiation = Negotiation::new().allowed(&allowed).consented(&consented);
+
+        let negotiated = negotiation.negotiate([
+            MyScope::ContactsReadonly,
+            MyScope::Contacts,
+            MyScope::Calendar,
+            MyScope::Unknown("files".to_string()),
+        ]);
+
+        assert_eq!(negotiated, Negotiated {
+            granted: vec![MyScope::ContactsReadonly],
+            dropped: vec![
+                (MyScope::Contacts, DropReason::NotConsented),
+                (MyScope::Calendar, DropReason::NotAllowed),
+                (MyScope::Unknown("files".to_string()), DropReason::Unknown),
+            ],
+        });
+        assert!(!negotiated.is_complete());
+
+        let negotiated = Negotiation::new().negotiate([MyScope::Calendar]);
+        assert!(negotiated.is_complete());
+        assert_eq!(negotiated.granted, vec![MyScope::Calendar]);
+    }
+
+    #[test]
+    fn test_negotiate_duplicates() {
+        let negotiated = Negotiation::new()
+            .allowed(&[MyScope::Calendar, MyScope::ContactsReadonly])
+            .negotiate([
+                MyScope::Calendar,
+                MyScope::Contacts,
+                MyScope::ContactsReadonly,
+                MyScope::Calendar,
+                MyScope::Contacts,
+            ]);
+
+        assert_eq!(negotiated.granted, vec![MyScope::Calendar, MyScope::ContactsReadonly]);
+        assert_eq!(negotiated.dropped, vec![(MyScope::Contacts, DropReason::NotAllowed)]);
+    }
+
+    #[test]
+    fn test_downscope() {
+        let negotiated = downscope([MyScope::Calendar, MyScope::Contacts], &[MyScope::Calendar]);
+        assert_eq!(negotiated.granted, vec![MyScope::Calendar]);
+        assert_eq!(negotiated.dropped, vec![(MyScope::Contacts, DropReason::NotInSubjectToken)]);
+    }
+
+    #[test]
+    #[cfg(feature = "hierarchy")]
+    fn test_negotiate_hierarchy() {
+        let negotiated = Negotiation::new()
+            .allowed(&[MyScope::Contacts])
+            .negotiate([MyScope::ContactsReadonly, MyScope::Calendar]);
+
+        assert_eq!(negotiated.granted, vec![MyScope::ContactsReadonly]);
+        assert_eq!(negotiated.dropped, vec![(MyScope::Calendar, DropReason::NotAllowed)]);
+
+        let negotiated = downscope([MyScope::Contacts, MyScope::ContactsReadonly], &[MyScope::ContactsReadonly]);
+        assert_eq!(negotiated.granted, vec![MyScope::ContactsReadonly]);
+        assert_eq!(negotiated.dropped, vec![(MyScope::Contacts, DropReason::NotInSubjectToken)]);
+    }
+
+    #[test]
+    #[cfg(feature = "hierarchy")]
+    fn test_negotiate_never_narrows() {
+        // The narrower allowed scope included in the requested scope is not granted instead
+        let negotiated = Negotiation::new()
+            .allowed(&[MyScope::ContactsReadonly])
+            .negotiate([MyScope::Contacts]);
+
+        assert!(negotiated.granted.is_empty());
+        assert_eq!(negotiated.dropped, vec![(MyScope::Contacts, DropReason::NotAllowed)]);
+    }
+}
@@ -0,0 +1,120 @@
+//! Standard OpenID Connect scopes
+//!
+//! [`OidcScope`] contains the scope values defined by
+//! [OpenID Connect Core 1.0 section 5.4](https://openid.net/specs/openid-connect-core-1_0.html#ScopeClaims),
+//! with the standard claims each scope gives access to.
+//!
+//! ```
+//! use scopes_rs::{list::ScopeList, oidc::OidcScope};
+//!
+//! let scopes: ScopeList<OidcScope> = "openid email".parse().unwrap();
+//! assert_eq!(scopes.as_slice(), &[OidcScope::Openid, OidcScope::Email]);
+//! assert_eq!(OidcScope::Email.claims(), &["email", "email_verified"]);
+//! ```
+//!
+//! # Embedding OIDC scopes
+//!
+//! A custom scope enum can wrap [`OidcScope`] in a nested variant, so policies can mix OpenID
+//! Connect and API scopes. The OIDC scope names are prefixed with the name of the variant.
+//!
+//! ```
+//! use scopes_rs::{derive::Scope, oidc::OidcScope, policy::IntoPolicy};
+//!
+//! #[derive(Clone, Debug, PartialEq, Scope)]
+//! #[scope(display)]
+//! enum MyScope {
+//!     #[scope(nested)]
+//!     Oidc(OidcScope),
+//!     FilesRead,
+//! }
+//!
+//! let scope: MyScope = "oidc.offline_access".parse().unwrap();
+//! assert_eq!(scope, MyScope::Oidc(OidcScope::OfflineAccess));
+//! assert_eq!(scope.to_string(), "oidc.offline_access");
+//!
+//! let policy = MyScope::Oidc(OidcScope::Openid).into_policy() & MyScope::FilesRead;
+//! assert!(policy.verify(&[MyScope::Oidc(OidcScope::Openid), MyScope::FilesRead]));
+//! ```
+//!
+//! The OIDC scope names can also be kept as is with the `"{0}"` name template, like
+//! `#[scope(rename = "{0}")] Oidc(OidcScope)`. The name is then split with the separator of the
+//! custom enum, so this only works with a separator that doesn't appear in the OIDC scope names :
+//! with `_`, `offline_access` would not be parsed.
+
+use crate::derive::Scope;
+
+/// The standard OpenID Connect scopes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Scope)]
+#[scope(rename_all = "snake_case", display)]
+pub enum OidcScope {
+
+    /// Authenticate with OpenID Connect
+    Openid,
+
+    /// Access your default profile information
+    Profile,
+
+    /// Access your email address
+    Email,
+
+    /// Access your physical address
+    Address,
+
+    /// Access your phone number
+    Phone,
+
+    /// Access your information while you are offline
+    OfflineAccess,
+}
+
+impl OidcScope {
+
+    /// The standard claims this scope gives access to
+    ///
+    /// The `openid` scope gives access to the `sub` claim, which is always returned.
+    /// The `offline_access` scope gives access to a refresh token, but to no claim.
+    pub const fn claims(&self) -> &'static [&'static str] {
+        match self {
+            OidcScope::Openid => &["sub"],
+            OidcScope::Profile => &[
+                "name", "family_name", "given_name", "middle_name", "nickname", "preferred_username",
+                "profile", "picture", "website", "gender", "birthdate", "zoneinfo", "locale", "updated_at",
+            ],
+            OidcScope::Email => &["email", "email_verified"],
+            OidcScope::Address => &["address"],
+            OidcScope::Phone => &["phone_number", "phone_number_verified"],
+            OidcScope::OfflineAccess => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{derive::Scope, oidc::OidcScope};
+
+    #[test]
+    fn test_oidc_scope_names() {
+        let names: Vec<String> = OidcScope::ALL.iter().map(ToString::to_string).collect();
+        assert_eq!(names, ["openid", "profile", "email", "address", "phone", "offline_access"]);
+
+        for scope in OidcScope::ALL {
+            assert_eq!(*scope, scope.to_string().parse::<OidcScope>().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_oidc_nested_snake_case() {
+        #[derive(Clone, Debug, PartialEq, Scope)]
+        #[scope(rename_all = "snake_case", display)]
+        enum MyScope {
+            #[scope(nested)]
+            Oidc(OidcScope),
+            FilesRead,
+        }
+
+        let scope: MyScope = "oidc_offline_access".parse().unwrap();
+        assert_eq!(scope, MyScope::Oidc(OidcScope::OfflineAccess));
+        assert_eq!(scope.to_string(), "oidc_offline_access");
+        assert_eq!(MyScope::FilesRead, "files_read".parse().unwrap());
+    }
+}
@@ -0,0 +1,165 @@
+use std::{convert::Infallible, fmt::Display};
+
+use crate::{dynamic::DynamicScope, error::PolicyConversionError, policy::Policy, scope::Scope};
+
+impl<S> Policy<S> where S: Scope {
+
+    /// Convert the scopes of a policy to another scope type
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// # use scopes_rs::policy::IntoPolicy;
+    /// # #[derive(Clone, Debug, PartialEq, Scope)]
+    /// # enum MyScope {Foo, Bar}
+    /// # #[derive(Clone, Debug, PartialEq, Scope)]
+    /// # enum OtherScope {Foo, Bar}
+    /// let policy = MyScope::Foo.into_policy() | MyScope::Bar;
+    /// 
+    /// let other = policy.map(|scope| match scope {
+    ///     MyScope::Foo => OtherScope::Foo,
+    ///     MyScope::Bar => OtherScope::Bar,
+    /// });
+    /// 
+    /// assert_eq!(other, OtherScope::Foo.into_policy() | OtherScope::Bar);
+    /// ```
+    pub fn map<T, F>(self, mut f: F) -> Policy<T>
+    where
+        T: Scope,
+        F: FnMut(S) -> T,
+    {
+        match self.try_map_inner(&mut |scope| Ok::<T, Infallible>(f(scope))) {
+            Ok(policy) => policy,
+            Err((err, _)) => match err {},
+        }
+    }
+
+    /// Try to convert the scopes of a policy to another scope type
+    /// 
+    /// Every scope is converted, and all the errors are returned if a conversion fails.
+    pub fn try_map<T, E, F>(self, mut f: F) -> Result<Policy<T>, Vec<E>>
+    where
+        T: Scope,
+        F: FnMut(S) -> Result<T, E>,
+    {
+        self.try_map_inner(&mut f).map_err(|(first, others)| {
+            let mut errors = vec![first];
+            errors.extend(others);
+            errors
+        })
+    }
+
+    // Convert the scopes of a policy, failing with the first error and the errors of the
+    // other scopes, so a failure always has at least one error
+    fn try_map_inner<T, E, F>(self, f: &mut F) -> Result<Policy<T>, (E, Vec<E>)>
+    where
+        T: Scope,
+        F: FnMut(S) -> Result<T, E>,
+    {
+        let map_all = |policies: Vec<Policy<S>>, f: &mut F| -> Result<Vec<Policy<T>>, (E, Vec<E>)> {
+            let mut mapped = Vec::new();
+            let mut error: Option<(E, Vec<E>)> = None;
+
+            for policy in policies {
+                match (policy.try_map_inner(f), error.as_mut()) {
+                    (Ok(policy), _) => mapped.push(policy),
+                    (Err(err), None) => error = Some(err),
+                    (Err((first, others)), Some((_, errors))) => {
+                        errors.push(first);
+                        errors.extend(others);
+                    },
+                }
+            }
+
+            match error {
+                Some(err) => Err(err),
+                None => Ok(mapped),
+            }
+        };
+
+        match self {
+            Policy::Scope(scope) => f(scope).map(Policy::Scope).map_err(|err| (err, Vec::new())),
+            Policy::OneOf(policies) => map_all(policies, f).map(Policy::OneOf),
+            Policy::AllOf(policies) => map_all(policies, f).map(Policy::AllOf),
+            Policy::Not(policy) => policy.try_map_inner(f).map(|p| Policy::Not(Box::new(p))),
+            Policy::AllowAll => Ok(Policy::AllowAll),
+            Policy::DenyAll => Ok(Policy::DenyAll),
+        }
+    }
+
+    /// Convert a policy to a policy of [`DynamicScope`]s, using the scope names given by
+    /// the [`Display`] implementation of the scopes, split with a separator.
+    /// 
+    /// Fails with the names of the scopes that are not valid dynamic scopes, because
+    /// they contain empty labels.
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// # use scopes_rs::policy::IntoPolicy;
+    /// use scopes_rs::dynamic::DynamicScope;
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// #[scope(display)]
+    /// enum ApiScope {
+    ///     FilesRead,
+    ///     FilesWrite,
+    /// }
+    /// 
+    /// let policy = ApiScope::FilesRead.into_policy() & ApiScope::FilesWrite;
+    /// let dynamic = policy.clone().into_dynamic(".").unwrap();
+    /// 
+    /// assert_eq!(dynamic, "files.read".parse::<DynamicScope>().unwrap().into_policy() & "files.write".parse::<DynamicScope>().unwrap());
+    /// assert_eq!(dynamic.into_typed::<ApiScope>().unwrap(), policy);
+    /// ```
+    pub fn into_dynamic(self, separator: &str) -> Result<Policy<DynamicScope>, PolicyConversionError>
+    where
+        S: Display,
+    {
+        self.try_map(|scope| {
+            let name = scope.to_string();
+            DynamicScope::new(&name, separator).map_err(|_| name)
+        })
+        .map_err(PolicyConversionError)
+    }
+}
+
+impl Policy<DynamicScope> {
+
+    /// Convert a policy of [`DynamicScope`]s to a policy of another scope type, by parsing
+    /// the name of each scope.
+    /// 
+    /// Fails with the names of all the scopes that could not be parsed, or that are parsed as
+    /// the catch-all variant of `T`, since unknown scopes would never be granted by the policy.
+    /// 
+    /// The names are parsed with [`FromStr`](std::str::FromStr), so a policy requiring an internal
+    /// scope of `T`, which is never parsed, can't be converted back to `T` either.
+    /// 
+    /// ```
+    /// # use scopes_rs::derive::Scope;
+    /// # use scopes_rs::policy::IntoPolicy;
+    /// use scopes_rs::dynamic::DynamicScope;
+    /// 
+    /// #[derive(Clone, Debug, PartialEq, Scope)]
+    /// enum ApiScope {
+    ///     FilesRead,
+    /// }
+    /// 
+    /// let scope = |name: &str| name.parse::<DynamicScope>().unwrap();
+    /// let policy = scope("files.read").into_policy() | scope("files.write") | scope("admin");
+    /// 
+    /// let err = policy.into_typed::<ApiScope>().unwrap_err();
+    /// assert_eq!(err.0, vec!["files.write", "admin"]);
+    /// ```
+    pub fn into_typed<T>(self) -> Result<Policy<T>, PolicyConversionError>
+    where
+        T: Scope,
+    {
+        self.try_map(|scope| {
+            let name = scope.to_string();
+            match name.parse::<T>() {
+                Ok(scope) if !scope.is_catch_all() => Ok(scope),
+                _ => Err(name),
+            }
+        })
+        .map_err(PolicyConversionError)
+    }
+}
@@ -1,7 +1,9 @@
 //! Policy creation and verification
 
+#[allow(clippy::module_inception)]
 mod policy;
 mod builder;
+mod convert;
 
 #[cfg(test)]
 mod tests;
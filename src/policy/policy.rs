@@ -1,6 +1,6 @@
 use std::ops::{BitAnd, BitOr, Not};
 
-use crate::scope::{AsScopeRef, Scope};
+use crate::{scope::{AsScopeRef, Scope}, wildcard::{AsGrantRef, WildcardScope}};
 
 /// A policy to verify a set of scopes
 /// 
@@ -19,7 +19,7 @@ use crate::scope::{AsScopeRef, Scope};
 /// You can also use a [`PolicyBuilder<S>`](crate::policy::PolicyBuilder) to build
 /// complex policies.
 /// 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub enum Policy<S: Scope> {
 
     /// Requires a scope to be present
@@ -38,15 +38,10 @@ pub enum Policy<S: Scope> {
     AllowAll,
 
     /// Policy that accepts nothing
+    #[default]
     DenyAll,
 }
 
-impl<S> Default for Policy<S> where S: Scope {
-    fn default() -> Self {
-        Policy::DenyAll
-    }
-}
-
 impl<S> Clone for Policy<S>
 where S: Scope + Clone {
     fn clone(&self) -> Self {
@@ -71,11 +66,14 @@ impl<S> Policy<S> where S: Scope {
     {
         match self {
             
+            // Catch-all scopes are never matched
+            Policy::Scope(required) if required.is_catch_all() => false,
+
             #[cfg(not(feature = "hierarchy"))]
-            Policy::Scope(required) => scopes.into_iter().find(|s| s.as_scope_ref() == required).is_some(),
+            Policy::Scope(required) => scopes.into_iter().find(|s| !s.as_scope_ref().is_catch_all() && s.as_scope_ref() == required).is_some(),
 
             #[cfg(feature = "hierarchy")]
-            Policy::Scope(required) => scopes.into_iter().any(|s| s.as_scope_ref().includes(required)),
+            Policy::Scope(required) => scopes.into_iter().any(|s| !s.as_scope_ref().is_catch_all() && s.as_scope_ref().includes(required)),
 
             Policy::Not(policy) => !policy.verify(scopes),
             Policy::OneOf(policies) => policies.iter().any(|p| p.verify(scopes.clone())),
@@ -84,6 +82,26 @@ impl<S> Policy<S> where S: Scope {
             Policy::DenyAll => false,
         }
     }
+
+    /// Check if a set of grants, which can contain wildcards, is authorized by a policy
+    /// 
+    /// A required scope is present if a scope or a wildcard of the grants gives access to it.
+    /// See the [`wildcard`](crate::wildcard) module for more details.
+    pub fn verify_grants<Iterator>(&self, grants: Iterator) -> bool
+    where
+        S: WildcardScope,
+        Iterator: IntoIterator + Clone,
+        Iterator::Item: AsGrantRef<S>,
+    {
+        match self {
+            Policy::Scope(required) => grants.into_iter().any(|g| g.as_grant_ref().grants(required)),
+            Policy::Not(policy) => !policy.verify_grants(grants),
+            Policy::OneOf(policies) => policies.iter().any(|p| p.verify_grants(grants.clone())),
+            Policy::AllOf(policies) => policies.iter().all(|p| p.verify_grants(grants.clone())),
+            Policy::AllowAll => true,
+            Policy::DenyAll => false,
+        }
+    }
 }
 
 impl<S, I> BitAnd<I> for Policy<S>
@@ -201,13 +201,33 @@ fn test_hierarchy() {
     let policy = MyScope::FooBar.into_policy();
 
     #[cfg(feature = "hierarchy")]
-    assert_eq!(true, policy.verify(&[MyScope::Foo]));
+    assert!(policy.verify(&[MyScope::Foo]));
 
     #[cfg(not(feature = "hierarchy"))]
-    assert_eq!(false, policy.verify(&[MyScope::Foo]));
+    assert!(!policy.verify(&[MyScope::Foo]));
 }
 
 #[test]
 fn test_default_policy() {
     assert_eq!(Policy::<MyScope>::DenyAll, Policy::<MyScope>::default())
 }
+
+#[test]
+fn test_map_policy() {
+    let policy = (MyScope::Foo.into_policy() | MyScope::Bar) & !MyScope::FooBar.into_policy();
+
+    let mapped = policy.clone().map(|scope| match scope {
+        MyScope::Foo => MyScope::Bar,
+        other => other,
+    });
+    assert_eq!(mapped, (MyScope::Bar.into_policy() | MyScope::Bar) & !MyScope::FooBar.into_policy());
+
+    // Every failed conversion is reported
+    let errors = policy.try_map(|scope| match scope {
+        MyScope::Foo => Ok(scope),
+        other => Err(other),
+    }).unwrap_err();
+    assert_eq!(errors, vec![MyScope::Bar, MyScope::FooBar]);
+
+    assert_eq!(Ok(Policy::<MyScope>::AllowAll), Policy::<MyScope>::AllowAll.try_map(Ok::<MyScope, ()>));
+}
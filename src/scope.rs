@@ -11,7 +11,24 @@ use crate::policy::{Policy, PolicyBuilder};
 /// 
 /// The [`FromStr`] implementation is used to parse scopes
 /// from strings.
-pub trait Scope: FromStr + PartialEq {}
+pub trait Scope: FromStr + PartialEq {
+
+    /// Check if this scope is a catch-all for unknown scopes.
+    /// 
+    /// Catch-all scopes are never matched by a [`Policy`], neither as a
+    /// required scope nor as a granted scope. Defaults to `false`.
+    fn is_catch_all(&self) -> bool {
+        false
+    }
+
+    /// Get the scopes this scope expands to, if it is a group of scopes.
+    /// 
+    /// Groups are replaced by their members when parsing a [`ScopeList`](crate::list::ScopeList).
+    /// Defaults to `None`.
+    fn expansion(&self) -> Option<Vec<Self>> where Self: Sized {
+        None
+    }
+}
 
 #[cfg(feature = "hierarchy")]
 /// A trait implemented by types representing a scope.
@@ -21,7 +38,24 @@ pub trait Scope: FromStr + PartialEq {}
 /// 
 /// The [`Hierarchized`] trait is only required with the `hierarchy`
 /// feature.
-pub trait Scope: FromStr + PartialEq + Hierarchized {}
+pub trait Scope: FromStr + PartialEq + Hierarchized {
+
+    /// Check if this scope is a catch-all for unknown scopes.
+    /// 
+    /// Catch-all scopes are never matched by a [`Policy`], neither as a
+    /// required scope nor as a granted scope. Defaults to `false`.
+    fn is_catch_all(&self) -> bool {
+        false
+    }
+
+    /// Get the scopes this scope expands to, if it is a group of scopes.
+    /// 
+    /// Groups are replaced by their members when parsing a [`ScopeList`](crate::list::ScopeList).
+    /// Defaults to `None`.
+    fn expansion(&self) -> Option<Vec<Self>> where Self: Sized {
+        None
+    }
+}
 
 
 /// Used to do a cheap reference-to-reference conversion
@@ -45,15 +79,15 @@ impl<S: Scope> AsScopeRef<S> for S {
     }
 }
 
-impl<'a, S: Scope> AsScopeRef<S> for &'a S {
+impl<S: Scope> AsScopeRef<S> for &S {
     fn as_scope_ref(&self) -> &S {
-        *self
+        self
     }
 }
 
-impl<'a, 'b, S: Scope> AsScopeRef<S> for &'a &'b S {
+impl<S: Scope> AsScopeRef<S> for &&S {
     fn as_scope_ref(&self) -> &S {
-        **self
+        self
     }
 }
 
@@ -62,6 +96,7 @@ mod tests {
     use crate::scope::AsScopeRef;
 
     #[test]
+    #[allow(clippy::needless_borrow)]
     fn test_as_scope_ref() {
 
         let scope = "foo".to_string();
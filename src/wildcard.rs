@@ -0,0 +1,225 @@
+//! Wildcard grants, like `files.*` or `*`
+//!
+//! A wildcard grants every scope whose labels start with the labels of the wildcard,
+//! and `*` grants every scope. Wildcards can only be granted : they are not scopes, and
+//! can't be required by a [`Policy`](crate::policy::Policy).
+//!
+//! Wildcards are recognized by the derive macro with the `#[scope(wildcard)]` attribute,
+//! which implements [`WildcardScope`] for the enum. Grants can then be parsed as a [`Grant<S>`],
+//! and verified with [`Policy::verify_grants`](crate::policy::Policy::verify_grants).
+//!
+//! ```
+//! # use std::str::FromStr;
+//! use scopes_rs::{derive::Scope, policy::IntoPolicy, wildcard::Grant};
+//!
+//! #[derive(Clone, Debug, PartialEq, Scope)]
+//! #[scope(wildcard)]
+//! enum MyScope {
+//!     FilesRead,
+//!     FilesWrite,
+//!     Admin,
+//! }
+//!
+//! let grants: Vec<Grant<MyScope>> = vec![Grant::from_str("files.*").unwrap()];
+//!
+//! assert!(MyScope::FilesWrite.into_policy().verify_grants(&grants));
+//! assert!(!MyScope::Admin.into_policy().verify_grants(&grants));
+//! ```
+//!
+//! A wildcard can't be used as a requirement :
+//!
+//! ```compile_fail
+//! # use std::str::FromStr;
+//! # use scopes_rs::{derive::Scope, policy::IntoPolicy, wildcard::Grant};
+//! # #[derive(Clone, Debug, PartialEq, Scope)]
+//! # #[scope(wildcard)]
+//! # enum MyScope { FilesRead, FilesWrite }
+//! let wildcard: Grant<MyScope> = Grant::from_str("files.*").unwrap();
+//! let policy = wildcard.into_policy();
+//! ```
+
+use std::str::FromStr;
+
+use crate::scope::Scope;
+
+/// A label of a scope name, compared to the labels of a [`Wildcard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WildcardLabel<'a> {
+
+    /// A literal label, which ignores the ASCII case for case-insensitive scopes
+    Literal(&'a str),
+
+    /// The value of a parameter, which is always compared exactly like when parsing the scope
+    Param(&'a str),
+}
+
+/// A wildcard granting every scope whose labels start with its labels
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Wildcard {
+    labels: Vec<String>,
+}
+
+impl Wildcard {
+
+    /// Parse a wildcard from a scope name without its prefix.
+    ///
+    /// The name must be `*`, or labels separated by `separator` followed by
+    /// the separator and `*`. Labels can't be empty or contain a `*`.
+    ///
+    /// ```
+    /// use scopes_rs::wildcard::Wildcard;
+    ///
+    /// assert_eq!(Wildcard::parse("files.*", ".").unwrap().labels(), &["files"]);
+    /// assert!(Wildcard::parse("*", ".").unwrap().labels().is_empty());
+    /// assert!(Wildcard::parse("files", ".").is_none());
+    /// assert!(Wildcard::parse("files.*.read", ".").is_none());
+    /// ```
+    pub fn parse(name: &str, separator: &str) -> Option<Self> {
+
+        if name == "*" {
+            return Some(Self { labels: Vec::new() });
+        }
+
+        if separator.is_empty() {
+            return None;
+        }
+
+        let name = name.strip_suffix('*')?.strip_suffix(separator)?;
+        let labels: Vec<String> = name.split(separator).map(String::from).collect();
+
+        if labels.iter().any(|l| l.is_empty() || l.contains('*')) {
+            return None;
+        }
+
+        Some(Self { labels })
+    }
+
+    /// The labels a scope must start with to be granted by this wildcard
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Check if this wildcard grants a scope with the given labels.
+    ///
+    /// With `case_insensitive`, the literal labels ignore the ASCII case, but parameter values
+    /// must be equal, so a wildcard never grants more than the scope names it stands for.
+    pub fn matches(&self, labels: &[WildcardLabel<'_>], case_insensitive: bool) -> bool {
+        self.labels.len() <= labels.len()
+            && self.labels.iter().zip(labels).all(|(a, b)| match b {
+                WildcardLabel::Literal(b) if case_insensitive => a.eq_ignore_ascii_case(b),
+                WildcardLabel::Literal(b) | WildcardLabel::Param(b) => a == b,
+            })
+    }
+}
+
+/// A scope type that can be granted with wildcards
+///
+/// This is implemented by the derive macro with the `#[scope(wildcard)]` attribute.
+pub trait WildcardScope: Scope {
+
+    /// Parse a wildcard grant, or return `None` if the string is not a wildcard
+    fn parse_wildcard(s: &str) -> Option<Wildcard>;
+
+    /// Check if this scope is granted by a wildcard
+    fn matches_wildcard(&self, wildcard: &Wildcard) -> bool;
+}
+
+/// A granted scope or wildcard
+#[derive(Debug, Clone, PartialEq)]
+pub enum Grant<S: WildcardScope> {
+
+    /// A single scope
+    Scope(S),
+
+    /// A wildcard
+    Wildcard(Wildcard),
+}
+
+impl<S: WildcardScope> Grant<S> {
+
+    /// Check if this grant gives access to a scope
+    /// 
+    /// Catch-all scopes are never granted.
+    pub fn grants(&self, scope: &S) -> bool {
+        match self {
+            _ if scope.is_catch_all() => false,
+            Grant::Scope(granted) if granted.is_catch_all() => false,
+
+            #[cfg(not(feature = "hierarchy"))]
+            Grant::Scope(granted) => granted == scope,
+
+            #[cfg(feature = "hierarchy")]
+            Grant::Scope(granted) => granted.includes(scope),
+
+            Grant::Wildcard(wildcard) => scope.matches_wildcard(wildcard),
+        }
+    }
+}
+
+impl<S: WildcardScope> From<S> for Grant<S> {
+    fn from(value: S) -> Self {
+        Grant::Scope(value)
+    }
+}
+
+impl<S: WildcardScope> FromStr for Grant<S> {
+    type Err = S::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match S::parse_wildcard(s) {
+            Some(wildcard) => Ok(Grant::Wildcard(wildcard)),
+            None => S::from_str(s).map(Grant::Scope),
+        }
+    }
+}
+
+/// Used to do a cheap reference-to-reference conversion
+pub trait AsGrantRef<S: WildcardScope> {
+    /// Converts this type to a reference
+    fn as_grant_ref(&self) -> &Grant<S>;
+}
+
+impl<S: WildcardScope> AsGrantRef<S> for Grant<S> {
+    fn as_grant_ref(&self) -> &Grant<S> {
+        self
+    }
+}
+
+impl<S: WildcardScope> AsGrantRef<S> for &Grant<S> {
+    fn as_grant_ref(&self) -> &Grant<S> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wildcard::{Wildcard, WildcardLabel::{Literal, Param}};
+
+    #[test]
+    fn test_parse_wildcard() {
+        assert_eq!(Some(&["files".to_string(), "read".to_string()][..]), Wildcard::parse("files:read:*", ":").as_ref().map(Wildcard::labels));
+        assert!(Wildcard::parse("*", "").is_some());
+        assert!(Wildcard::parse("files*", "").is_none());
+        assert!(Wildcard::parse(".*", ".").is_none());
+        assert!(Wildcard::parse("files..*", ".").is_none());
+        assert!(Wildcard::parse("files.**", ".").is_none());
+    }
+
+    #[test]
+    fn test_matches() {
+        let wildcard = Wildcard::parse("files.*", ".").unwrap();
+
+        assert!(wildcard.matches(&[Literal("files")], false));
+        assert!(wildcard.matches(&[Literal("files"), Literal("read")], false));
+        assert!(!wildcard.matches(&[Literal("Files"), Literal("read")], false));
+        assert!(wildcard.matches(&[Literal("Files"), Literal("read")], true));
+        assert!(!wildcard.matches(&[Literal("filesystem")], false));
+        assert!(!wildcard.matches(&[], false));
+
+        // Parameter values never ignore the case
+        assert!(wildcard.matches(&[Param("files")], true));
+        assert!(!wildcard.matches(&[Param("Files")], true));
+
+        assert!(Wildcard::parse("*", ".").unwrap().matches(&[Literal("admin")], false));
+    }
+}
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+use scopes_rs::{dynamic::DynamicScope, policy::{IntoPolicy, Policy}, scope::Scope as _};
+
+#[cfg(feature = "hierarchy")]
+use scopes_rs::hierarchy::Hierarchized;
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(prefix = "api/", case_insensitive, display)]
+enum MyScope {
+    Foo,
+    FooBar,
+    #[scope(other)]
+    Unknown(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(separator = ":", display)]
+enum RepoScope {
+    #[scope(rename = "repo:{0}")]
+    Repo(u32),
+    #[scope(other)]
+    Other { name: Box<str> },
+}
+
+#[test]
+fn test_parse_unknown() {
+    assert_eq!(MyScope::FooBar, MyScope::from_str("API/Foo.Bar").expect("Could not parse scope"));
+
+    // The original input is preserved
+    assert_eq!(MyScope::Unknown(" Billing.Read".to_string()), MyScope::from_str(" Billing.Read").expect("Parsing should never fail"));
+    assert_eq!(MyScope::Unknown(String::new()), MyScope::from_str("").expect("Parsing should never fail"));
+
+    assert_eq!(RepoScope::Repo(1), RepoScope::from_str("repo:1").expect("Could not parse scope"));
+    assert_eq!(RepoScope::Other { name: "repo:abc".into() }, RepoScope::from_str("repo:abc").expect("Parsing should never fail"));
+}
+
+#[test]
+fn test_display_unknown() {
+    assert_eq!("billing.read", MyScope::Unknown("billing.read".to_string()).to_string());
+    assert_eq!("repo:abc", RepoScope::Other { name: "repo:abc".into() }.to_string());
+    assert_eq!("api/foo", MyScope::Foo.to_string());
+}
+
+#[test]
+fn test_catch_all() {
+    assert!(MyScope::Unknown("foo".to_string()).is_catch_all());
+    assert!(!MyScope::Foo.is_catch_all());
+
+    assert_eq!(MyScope::ALL, &[MyScope::Foo, MyScope::FooBar]);
+    assert_eq!(MyScope::from_index(2), None);
+}
+
+#[test]
+fn test_policy_unknown() {
+    let unknown = MyScope::Unknown("billing".to_string());
+
+    // Unknown scopes are never matched, even when equal
+    assert!(!unknown.clone().into_policy().verify(&[&unknown]));
+    assert!(!MyScope::Foo.into_policy().verify(&[&unknown]));
+    assert!(MyScope::Foo.into_policy().verify(&[unknown.clone(), MyScope::Foo]));
+
+    let policy: Policy<MyScope> = !unknown.clone().into_policy();
+    assert!(policy.verify(&[unknown]));
+}
+
+#[test]
+fn test_into_typed_unknown() {
+    let scope = |name: &str| name.parse::<DynamicScope>().expect("Could not parse dynamic scope");
+    let policy = scope("api/foo").into_policy() & scope("billing.read") & scope("admin");
+
+    // Scopes parsed as the catch-all variant are conversion errors
+    let err = policy.into_typed::<MyScope>().expect_err("Unknown scopes should not be converted");
+    assert_eq!(err.0, vec!["billing.read", "admin"]);
+
+    let policy = scope("api/foo").into_policy() | scope("api/foo.bar");
+    assert_eq!(MyScope::Foo.into_policy() | MyScope::FooBar, policy.into_typed::<MyScope>().expect("Could not convert policy"));
+}
+
+#[cfg(feature = "hierarchy")]
+#[test]
+fn test_hierarchy_unknown() {
+    let unknown = MyScope::Unknown("api/foo.bar".to_string());
+
+    assert!(!MyScope::Foo.includes(&unknown));
+    assert!(!unknown.includes(&MyScope::FooBar));
+    assert!(MyScope::Foo.includes(&MyScope::FooBar));
+}
@@ -7,6 +7,7 @@ use scopes_rs::{error::ScopeParseError, policy::{IntoPolicy, Policy}};
 use scopes_rs::hierarchy::Hierarchized;
 
 #[derive(Debug, PartialEq, Scope)]
+#[scope(display)]
 enum MyScope {
     Foo,
     FooBar,
@@ -23,6 +24,13 @@ fn test_parsing() {
 
 }
 
+#[test]
+fn test_display() {
+    assert_eq!("foo", MyScope::Foo.to_string());
+    assert_eq!("foo.bar", MyScope::FooBar.to_string());
+    assert_eq!("baz", format!("{}", MyScope::Baz));
+}
+
 #[test]
 fn test_invalid_parsing() {
     assert!(MyScope::from_str("not_a_scope").is_err());
@@ -30,10 +38,20 @@ fn test_invalid_parsing() {
     assert!(MyScope::from_str("").is_err());
 
     // Check that generated implementation uses the correct error
-    match MyScope::from_str("") {
-        Ok(_) => panic!("Parsing an empty string should return an error"),
-        Err(ScopeParseError(_)) => (),
+    let ScopeParseError(_) = MyScope::from_str("").expect_err("Parsing an empty string should return an error");
+}
+
+#[test]
+fn test_enumerate() {
+    assert_eq!(4, MyScope::COUNT);
+    assert_eq!(&[MyScope::Foo, MyScope::FooBar, MyScope::Baz, MyScope::Bar], MyScope::ALL);
+
+    for (i, scope) in MyScope::ALL.iter().enumerate() {
+        assert_eq!(i, scope.index());
+        assert_eq!(Some(scope), MyScope::from_index(i).as_ref());
     }
+
+    assert_eq!(None, MyScope::from_index(MyScope::COUNT));
 }
 
 #[test]
@@ -0,0 +1,68 @@
+use scopes_macros::Scope;
+use scopes_rs::{dynamic::{DynamicScope, ScopeCatalog}, policy::{IntoPolicy, PolicyBuilder}};
+
+fn catalog() -> ScopeCatalog {
+    let mut catalog = ScopeCatalog::with_separator(":");
+    for name in ["plugin:files", "plugin:files:read", "plugin:files:write", "plugin:admin"] {
+        catalog.register(name).expect("Could not register scope");
+    }
+
+    #[cfg(feature = "hierarchy")]
+    catalog.include("plugin:admin", "plugin:files").expect("Could not include scope");
+
+    catalog
+}
+
+#[test]
+fn test_dynamic_policy() {
+    let catalog = catalog();
+    let scope = |name: &str| catalog.parse(name).expect("Could not parse scope");
+
+    let policy = scope("plugin:files:read").into_policy() & scope("plugin:files:write");
+
+    assert!(policy.verify(&[scope("plugin:files:read"), scope("plugin:files:write")]));
+    assert!(!policy.verify(&[scope("plugin:files:read")]));
+
+    #[cfg(feature = "hierarchy")]
+    {
+        assert!(policy.verify(&[scope("plugin:files")]));
+        assert!(policy.verify(&[scope("plugin:admin")]));
+    }
+
+    #[cfg(not(feature = "hierarchy"))]
+    assert!(!policy.verify(&[scope("plugin:files")]));
+
+    let policy = PolicyBuilder::not(scope("plugin:admin")).build();
+    assert!(policy.verify(&[scope("plugin:files")]));
+    assert!(!policy.verify(&[scope("plugin:admin")]));
+}
+
+#[test]
+fn test_dynamic_scope_parsing() {
+    let scope: DynamicScope = "plugin.files.read".parse().expect("Could not parse scope");
+
+    assert_eq!(vec!["plugin", "files", "read"], scope.labels().collect::<Vec<_>>());
+    assert!(scope.into_policy().verify(&["plugin.files.read".parse::<DynamicScope>().unwrap()]));
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(separator = ":", display)]
+enum PluginScope {
+    #[scope(rename = "plugin:files:read")]
+    FilesRead,
+    #[scope(rename = "plugin:files:write")]
+    FilesWrite,
+}
+
+#[test]
+fn test_dynamic_policy_from_typed() {
+    let catalog = catalog();
+    let scope = |name: &str| catalog.parse(name).expect("Could not parse scope");
+
+    // A converted policy is equal to the same policy loaded from the catalog
+    let typed = PluginScope::FilesRead.into_policy() & PluginScope::FilesWrite;
+    let dynamic = typed.clone().into_dynamic(":").expect("Could not convert policy");
+    assert_eq!(dynamic, scope("plugin:files:read").into_policy() & scope("plugin:files:write"));
+
+    assert_eq!(typed, dynamic.into_typed::<PluginScope>().expect("Could not convert policy"));
+}
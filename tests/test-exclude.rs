@@ -0,0 +1,60 @@
+#![cfg(feature = "hierarchy")]
+
+use scopes_macros::Scope;
+use scopes_rs::hierarchy::Hierarchized;
+
+use audit::AuditScope;
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case")]
+enum MyScope {
+    #[scope(exclude = AdminAuditLog)]
+    Admin,
+    AdminUsers,
+    AdminAuditLog,
+}
+
+// AdminAuditLog is still included in Admin through AdminAudit, which emits a warning
+mod audit {
+    #![allow(deprecated)]
+
+    use scopes_macros::Scope;
+
+    #[derive(Debug, PartialEq, Scope)]
+    #[scope(rename_all = "snake_case")]
+    pub enum AuditScope {
+        #[scope(exclude = AdminAuditLog)]
+        Admin,
+        AdminAudit,
+        AdminAuditLog,
+    }
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(infer_hierarchy = false)]
+enum FlatScope {
+    #[scope(include = FooBaz)]
+    Foo,
+    FooBar,
+    FooBaz,
+}
+
+#[test]
+fn test_exclude() {
+    assert!(MyScope::Admin.includes(&MyScope::AdminUsers));
+    assert!(!MyScope::Admin.includes(&MyScope::AdminAuditLog));
+}
+
+#[test]
+fn test_exclude_intermediate() {
+    assert!(AuditScope::Admin.includes(&AuditScope::AdminAudit));
+    assert!(AuditScope::AdminAudit.includes(&AuditScope::AdminAuditLog));
+    assert!(AuditScope::Admin.includes(&AuditScope::AdminAuditLog));
+}
+
+#[test]
+fn test_disabled_label_inclusions() {
+    assert!(!FlatScope::Foo.includes(&FlatScope::FooBar));
+    assert!(FlatScope::Foo.includes(&FlatScope::FooBaz));
+    assert!(!FlatScope::FooBar.includes(&FlatScope::Foo));
+}
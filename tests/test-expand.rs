@@ -0,0 +1,46 @@
+use scopes_macros::Scope;
+use scopes_rs::{list::ScopeList, policy::IntoPolicy, scope::Scope as _};
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case", display)]
+enum MyScope {
+    Openid,
+    Profile,
+    Email,
+    Address,
+    #[scope(default, expands_to = [Openid, Profile, Email])]
+    Basic,
+    #[scope(expands_to = [Basic, Address])]
+    Full,
+}
+
+#[test]
+fn test_expansion() {
+    assert_eq!(MyScope::Openid.expansion(), None);
+    assert_eq!(MyScope::Basic.expansion(), Some(vec![MyScope::Openid, MyScope::Profile, MyScope::Email]));
+    assert_eq!(MyScope::Full.expansion(), Some(vec![MyScope::Openid, MyScope::Profile, MyScope::Email, MyScope::Address]));
+}
+
+#[test]
+fn test_parse_groups() {
+    let list: ScopeList<MyScope> = "email basic".parse().expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), &[MyScope::Email, MyScope::Openid, MyScope::Profile]);
+
+    let list: ScopeList<MyScope> = "full".parse().expect("Could not parse scopes");
+    assert_eq!("openid profile email address", list.to_string());
+
+    // Groups only grant their members
+    assert!(MyScope::Address.into_policy().verify(&list));
+    assert!(!MyScope::Full.into_policy().verify(&list));
+
+    // Groups can still be parsed on their own
+    assert_eq!(MyScope::Basic, "basic".parse().expect("Could not parse scope"));
+}
+
+#[test]
+fn test_default_groups() {
+    // Default groups are expanded like parsed groups
+    let list = ScopeList::<MyScope>::parse_or_default("").expect("Could not get the default scopes");
+    assert_eq!(list.as_slice(), "basic".parse::<ScopeList<MyScope>>().expect("Could not parse scopes").as_slice());
+    assert_eq!(list.as_slice(), &[MyScope::Openid, MyScope::Profile, MyScope::Email]);
+}
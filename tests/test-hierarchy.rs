@@ -2,9 +2,8 @@
 
 use scopes_macros::Scope;
 use scopes_rs::hierarchy::Hierarchized;
-use strum::{EnumIter, IntoEnumIterator};
 
-#[derive(Clone, Debug, PartialEq, Scope, EnumIter)]
+#[derive(Clone, Debug, PartialEq, Scope)]
 enum MyScope {
 
     Foo,
@@ -83,10 +82,10 @@ fn test_reference_hierarchy() {
     }
 
     // Test all combinations
-    for sself in MyScope::iter() {
-        for other in MyScope::iter() {
+    for sself in MyScope::ALL {
+        for other in MyScope::ALL {
 
-            assert_eq!(sself.includes(&other), expected_includes(&sself, &other));
+            assert_eq!(sself.includes(other), expected_includes(sself, other));
 
         }
     }
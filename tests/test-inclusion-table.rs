@@ -0,0 +1,94 @@
+#![cfg(feature = "hierarchy")]
+
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+use scopes_rs::hierarchy::Hierarchized;
+
+#[derive(Debug, PartialEq, Scope)]
+enum BillingScope {
+    Invoices,
+    InvoicesRead,
+}
+
+// Declare the same scopes with and without the inclusion table
+macro_rules! scope_enum {
+    ($name:ident $(, $option:ident)?) => {
+        #[derive(Debug, PartialEq, Scope)]
+        $(#[scope($option)])?
+        enum $name {
+            Repos,
+            ReposRead,
+            #[scope(rename = "repos.{0}")]
+            Repo(u32),
+            #[scope(rename = "repos.{0}.read")]
+            RepoRead(u32),
+            #[scope(include = [ReposRead, Audit])]
+            Admin,
+            Audit,
+            Billing,
+            #[scope(nested)]
+            BillingScopes(BillingScope),
+            #[scope(other)]
+            Unknown(String),
+        }
+    };
+}
+
+scope_enum!(MatchScope);
+scope_enum!(TableScope, inclusion_table);
+
+macro_rules! big_scope {
+    ($($variant:ident)*) => {
+        #[derive(Debug, PartialEq, Scope)]
+        #[scope(inclusion_table)]
+        enum BigScope {
+            Repos,
+            $($variant,)*
+            #[scope(include = ReposR68)]
+            Support,
+        }
+    };
+}
+
+big_scope!(
+    ReposR0 ReposR1 ReposR2 ReposR3 ReposR4 ReposR5 ReposR6 ReposR7 ReposR8 ReposR9 ReposR10 ReposR11 ReposR12
+    ReposR13 ReposR14 ReposR15 ReposR16 ReposR17 ReposR18 ReposR19 ReposR20 ReposR21 ReposR22 ReposR23 ReposR24
+    ReposR25 ReposR26 ReposR27 ReposR28 ReposR29 ReposR30 ReposR31 ReposR32 ReposR33 ReposR34 ReposR35 ReposR36
+    ReposR37 ReposR38 ReposR39 ReposR40 ReposR41 ReposR42 ReposR43 ReposR44 ReposR45 ReposR46 ReposR47 ReposR48
+    ReposR49 ReposR50 ReposR51 ReposR52 ReposR53 ReposR54 ReposR55 ReposR56 ReposR57 ReposR58 ReposR59 ReposR60
+    ReposR61 ReposR62 ReposR63 ReposR64 ReposR65 ReposR66 ReposR67 ReposR68 ReposR69
+);
+
+#[test]
+fn test_inclusion_table() {
+    let names = [
+        "repos", "repos.read", "repos.1", "repos.2", "repos.1.read", "repos.2.read", "admin", "audit",
+        "billing", "billing.scopes.invoices", "billing.scopes.invoices.read", "unknown",
+    ];
+
+    for a in names {
+        for b in names {
+            let expected = MatchScope::from_str(a).unwrap().includes(&MatchScope::from_str(b).unwrap());
+            let included = TableScope::from_str(a).unwrap().includes(&TableScope::from_str(b).unwrap());
+            assert_eq!(expected, included, "{} includes {}", a, b);
+        }
+    }
+
+    assert!(TableScope::Repo(1).includes(&TableScope::RepoRead(1)));
+    assert!(!TableScope::Repo(1).includes(&TableScope::RepoRead(2)));
+    assert!(TableScope::Admin.includes(&TableScope::Audit));
+    assert!(TableScope::Billing.includes(&TableScope::BillingScopes(BillingScope::InvoicesRead)));
+}
+
+#[test]
+fn test_large_inclusion_table() {
+    assert!(BigScope::Repos.includes(&BigScope::ReposR0));
+    assert!(BigScope::Repos.includes(&BigScope::ReposR69));
+    assert!(!BigScope::ReposR69.includes(&BigScope::Repos));
+    assert!(!BigScope::ReposR3.includes(&BigScope::ReposR67));
+
+    assert!(BigScope::Support.includes(&BigScope::ReposR68));
+    assert!(!BigScope::Support.includes(&BigScope::ReposR69));
+    assert!(!BigScope::Support.includes(&BigScope::Repos));
+}
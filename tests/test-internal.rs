@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+use scopes_rs::{policy::IntoPolicy, wildcard::Grant};
+
+#[cfg(feature = "hierarchy")]
+use scopes_rs::hierarchy::Hierarchized;
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(wildcard, display)]
+enum MyScope {
+    Files,
+    FilesRead,
+    #[scope(internal)]
+    FilesPurge,
+    #[scope(skip_parse, rename = "service.{0}")]
+    Service(String),
+    Admin,
+}
+
+#[test]
+fn test_internal_parsing() {
+    assert_eq!(MyScope::FilesRead, MyScope::from_str("files.read").expect("Could not parse scope"));
+
+    assert!(MyScope::from_str("files.purge").is_err());
+    assert!(MyScope::from_str("service.billing").is_err());
+
+    // Internal scopes can still be displayed
+    assert_eq!("files.purge", MyScope::FilesPurge.to_string());
+    assert_eq!("service.billing", MyScope::Service("billing".to_string()).to_string());
+}
+
+#[test]
+fn test_internal_lists() {
+    assert_eq!(MyScope::scopes_supported(), ["files", "files.read", "admin"]);
+    assert!(MyScope::ALL.contains(&MyScope::FilesPurge));
+
+    assert!(MyScope::FilesPurge.is_internal());
+    assert!(MyScope::Service("billing".to_string()).is_internal());
+    assert!(!MyScope::Files.is_internal());
+}
+
+#[test]
+fn test_internal_policy() {
+    let policy = MyScope::FilesPurge.into_policy();
+
+    assert!(policy.verify(&[MyScope::FilesPurge]));
+    assert!(!policy.verify(&[MyScope::FilesRead]));
+
+    #[cfg(feature = "hierarchy")]
+    assert!(policy.verify(&[MyScope::Files]));
+
+    // Wildcards don't grant internal scopes
+    let grants: Vec<Grant<MyScope>> = vec![Grant::from_str("*").expect("Could not parse grant")];
+    assert!(!policy.verify_grants(&grants));
+    assert!(!MyScope::Service("billing".to_string()).into_policy().verify_grants(&grants));
+    assert!(MyScope::FilesRead.into_policy().verify_grants(&grants));
+}
+
+#[cfg(feature = "hierarchy")]
+#[test]
+fn test_internal_hierarchy() {
+    assert!(MyScope::Files.includes(&MyScope::FilesPurge));
+    assert!(!MyScope::FilesPurge.includes(&MyScope::Files));
+}
+
+#[test]
+fn test_internal_into_typed() {
+    let policy = MyScope::FilesPurge.into_policy() & MyScope::FilesRead;
+    let dynamic = policy.into_dynamic(".").expect("Could not convert policy");
+
+    // Internal scopes are not parsed, so they can't be converted back from their name
+    let err = dynamic.into_typed::<MyScope>().expect_err("Internal scopes should not be converted");
+    assert_eq!(err.0, vec!["files.purge"]);
+}
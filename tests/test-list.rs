@@ -0,0 +1,51 @@
+use scopes_macros::Scope;
+use scopes_rs::{error::ScopeParseError, list::{DefaultScopes, ScopeList}, policy::IntoPolicy};
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(display)]
+enum MyScope {
+    #[scope(default)]
+    Openid,
+    #[scope(default)]
+    Profile,
+    Email,
+    #[scope(internal, default)]
+    Audit,
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+enum NoDefaultScope {
+    Foo,
+}
+
+#[test]
+fn test_defaults() {
+    assert_eq!(MyScope::defaults(), [MyScope::Openid, MyScope::Profile, MyScope::Audit]);
+    assert_eq!(MyScope::defaults(), <MyScope as DefaultScopes>::defaults());
+    assert!(NoDefaultScope::defaults().is_empty());
+}
+
+#[test]
+fn test_parse_list() {
+    let list: ScopeList<MyScope> = "openid  email".parse().expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), &[MyScope::Openid, MyScope::Email]);
+    assert_eq!("openid email", list.to_string());
+
+    let ScopeParseError(name) = "openid audit".parse::<ScopeList<MyScope>>().expect_err("Internal scopes should not be parsed");
+    assert_eq!("audit", name);
+
+    assert!(MyScope::Email.into_policy().verify(&list));
+    assert!(!MyScope::Profile.into_policy().verify(&list));
+}
+
+#[test]
+fn test_parse_or_default() {
+    let list = ScopeList::<MyScope>::parse_or_default("email").expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), &[MyScope::Email]);
+
+    let list = ScopeList::<MyScope>::parse_or_default(" ").expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), MyScope::defaults().as_slice());
+
+    assert!(ScopeList::<NoDefaultScope>::parse_or_default("").expect("Could not parse scopes").is_empty());
+    assert!(ScopeList::<MyScope>::parse_or_default("unknown").is_err());
+}
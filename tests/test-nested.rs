@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+use scopes_rs::{list::ScopeList, policy::IntoPolicy, scope::Scope as _, wildcard::{Grant, WildcardScope}};
+
+#[cfg(feature = "hierarchy")]
+use scopes_rs::hierarchy::Hierarchized;
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case", display)]
+enum BillingScope {
+    Invoices,
+    InvoicesRead,
+    #[scope(expands_to = [Invoices, Payments])]
+    All,
+    Payments,
+    #[scope(other)]
+    Unknown(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(scope_name_getter, display)]
+enum AdminScope {
+    Root,
+    #[scope(default)]
+    Users,
+    #[scope(internal)]
+    Sync,
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(wildcard, display)]
+enum AppScope {
+    #[cfg_attr(feature = "hierarchy", scope(nested, child_include(Root = Billing)))]
+    #[cfg_attr(not(feature = "hierarchy"), scope(nested))]
+    Admin(AdminScope),
+
+    #[scope(nested)]
+    Billing(BillingScope),
+
+    #[scope(nested, rename = "partner.billing")]
+    PartnerBilling(BillingScope),
+
+    Partner,
+
+    Profile,
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(scope_name_getter)]
+enum ConsoleScope {
+    #[scope(default)]
+    Profile,
+    #[scope(nested)]
+    Admin(AdminScope),
+    #[scope(nested, rename = "team.admin")]
+    TeamAdmin(AdminScope),
+    #[scope(nested, internal)]
+    Ops(AdminScope),
+}
+
+#[test]
+fn test_parse_nested() {
+    let scope = |name: &str| AppScope::from_str(name).expect("Could not parse scope");
+
+    assert_eq!(AppScope::Billing(BillingScope::InvoicesRead), scope("billing.invoices_read"));
+    assert_eq!(AppScope::Admin(AdminScope::Root), scope("admin.root"));
+    assert_eq!(AppScope::PartnerBilling(BillingScope::Payments), scope("partner.billing.payments"));
+    assert_eq!(AppScope::Partner, scope("partner"));
+
+    assert!(AppScope::from_str("admin.unknown").is_err());
+    assert!(AppScope::from_str("billing").is_err());
+
+    // The catch-all variant of the nested scope is used for unknown nested scopes
+    let unknown = scope("billing.refunds");
+    assert_eq!(AppScope::Billing(BillingScope::Unknown("refunds".to_string())), unknown);
+    assert!(unknown.is_catch_all());
+    assert!(!unknown.clone().into_policy().verify(&[unknown]));
+}
+
+#[test]
+fn test_display_nested() {
+    assert_eq!("billing.invoices_read", AppScope::Billing(BillingScope::InvoicesRead).to_string());
+    assert_eq!("partner.billing.payments", AppScope::PartnerBilling(BillingScope::Payments).to_string());
+    assert_eq!("admin.users", AppScope::Admin(AdminScope::Users).to_string());
+}
+
+#[test]
+fn test_scope_name_nested() {
+    assert_eq!("profile", ConsoleScope::Profile.scope_name());
+    assert_eq!("admin.users", ConsoleScope::Admin(AdminScope::Users).scope_name());
+    assert_eq!("team.admin.root", ConsoleScope::TeamAdmin(AdminScope::Root).scope_name());
+}
+
+#[test]
+fn test_lists_nested() {
+    // The scopes of the nested scope enums are listed with the prefix of their variant, without internal scopes
+    assert!(AdminScope::Sync.is_internal());
+    assert_eq!(ConsoleScope::scopes_supported(), ["profile", "admin.root", "admin.users", "team.admin.root", "team.admin.users"]);
+    assert_eq!(ConsoleScope::defaults(), [
+        ConsoleScope::Profile,
+        ConsoleScope::Admin(AdminScope::Users),
+        ConsoleScope::TeamAdmin(AdminScope::Users),
+    ]);
+
+    let list = ScopeList::<ConsoleScope>::parse_or_default("").expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), ConsoleScope::defaults().as_slice());
+
+    assert_eq!(AppScope::scopes_supported().len(), 12);
+    assert!(AppScope::scopes_supported().iter().any(|name| name == "partner.billing.invoices_read"));
+}
+
+#[test]
+fn test_expand_nested() {
+    let list: ScopeList<AppScope> = "profile billing.all".parse().expect("Could not parse scopes");
+    assert_eq!(list.as_slice(), &[
+        AppScope::Profile,
+        AppScope::Billing(BillingScope::Invoices),
+        AppScope::Billing(BillingScope::Payments),
+    ]);
+}
+
+#[test]
+fn test_wildcard_nested() {
+    let grant: Grant<AppScope> = "billing.*".parse().expect("Could not parse grant");
+    assert!(grant.grants(&AppScope::Billing(BillingScope::Payments)));
+    assert!(!grant.grants(&AppScope::PartnerBilling(BillingScope::Payments)));
+
+    let wildcard = AppScope::parse_wildcard("partner.billing.*").expect("Could not parse wildcard");
+    assert!(AppScope::PartnerBilling(BillingScope::Payments).matches_wildcard(&wildcard));
+    assert!(!AppScope::Partner.matches_wildcard(&wildcard));
+}
+
+#[test]
+#[cfg(feature = "hierarchy")]
+fn test_hierarchy_nested() {
+    // Inclusions of the nested scope enum
+    assert!(AppScope::Billing(BillingScope::Invoices).includes(&AppScope::Billing(BillingScope::InvoicesRead)));
+    assert!(!AppScope::Billing(BillingScope::InvoicesRead).includes(&AppScope::Billing(BillingScope::Invoices)));
+    assert!(!AppScope::Billing(BillingScope::Invoices).includes(&AppScope::PartnerBilling(BillingScope::InvoicesRead)));
+
+    // Label inclusions of the parent enum
+    assert!(AppScope::Partner.includes(&AppScope::PartnerBilling(BillingScope::Payments)));
+    assert!(!AppScope::Partner.includes(&AppScope::Billing(BillingScope::Payments)));
+
+    // Inclusions across scope enums
+    assert!(AppScope::Admin(AdminScope::Root).includes(&AppScope::Billing(BillingScope::Payments)));
+    assert!(!AppScope::Admin(AdminScope::Users).includes(&AppScope::Billing(BillingScope::Payments)));
+    assert!(!AppScope::Admin(AdminScope::Root).includes(&AppScope::PartnerBilling(BillingScope::Payments)));
+
+    let policy = AppScope::Billing(BillingScope::InvoicesRead).into_policy();
+    assert!(policy.verify(&[AppScope::Admin(AdminScope::Root)]));
+    assert!(!policy.verify(&[AppScope::Admin(AdminScope::Users)]));
+}
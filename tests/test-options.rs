@@ -6,16 +6,19 @@ use scopes_macros::Scope;
 use scopes_rs::hierarchy::Hierarchized;
 
 #[derive(Debug, PartialEq, Scope)]
-#[scope(prefix = "myprefix/", separator = "_", scope_name_getter = true)]
+#[scope(prefix = "myprefix/", separator = "_", scope_name_getter = true, display)]
 enum MyScope {
     Foo,
+    #[scope(alias = "foobar", alias = "foo_bar_legacy")]
     FooBar,
     Bar,
     // Should not be included in MyScope::Bar
+    /// Sit on a stool
     #[scope(rename = "barstool")]
     BarStool,
     // Should be included in MyScope::Foo with hierarchy feature
-    #[scope(rename = "foo_baz")]
+    /// Ignored doc comment
+    #[scope(rename = "foo_baz", description = "Renamed scope", title = "Renamed", category = "Tests", icon = "rename")]
     RenameSeparated,
 }
 
@@ -38,6 +41,19 @@ fn test_parsing() {
 
 }
 
+#[test]
+fn test_aliases() {
+    assert_eq!(MyScope::FooBar, MyScope::from_str("myprefix/foobar").expect("Could not parse alias"));
+    assert_eq!(MyScope::FooBar, MyScope::from_str("myprefix/foo_bar_legacy").expect("Could not parse alias"));
+
+    // Aliases are prefixed like the scope name
+    assert!(MyScope::from_str("foobar").is_err());
+
+    // The canonical name is still used for display
+    assert_eq!("myprefix/foo_bar", MyScope::FooBar.scope_name());
+    assert_eq!("myprefix/foo_bar", MyScope::FooBar.to_string());
+}
+
 #[test]
 fn test_scope_name() {
     assert_eq!("myprefix/foo", MyScope::Foo.scope_name());
@@ -47,6 +63,22 @@ fn test_scope_name() {
     assert_eq!("myprefix/foo_baz", MyScope::RenameSeparated.scope_name());
 }
 
+#[test]
+fn test_metadata() {
+    assert_eq!(None, MyScope::Foo.description());
+    assert_eq!(Some("Sit on a stool"), MyScope::BarStool.description());
+    assert_eq!(Some("Renamed scope"), MyScope::RenameSeparated.description());
+
+    let metadata = MyScope::RenameSeparated.metadata();
+    assert_eq!("myprefix/foo_baz", metadata.name);
+    assert_eq!(Some("Renamed"), metadata.title);
+    assert_eq!(Some("Tests"), metadata.category);
+    assert_eq!(Some("rename"), metadata.icon);
+
+    assert_eq!(MyScope::COUNT, MyScope::METADATA.len());
+    assert_eq!("myprefix/barstool", MyScope::METADATA[MyScope::BarStool.index()].name);
+}
+
 #[test]
 #[cfg(feature = "hierarchy")]
 fn test_hierarchy() {
@@ -64,3 +96,80 @@ fn test_hierarchy() {
     assert!(!policy.verify(&[MyScope::Bar]));
 }
 
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(rename_all = "SCREAMING_SNAKE", display)]
+enum ScreamingScope {
+    Contacts,
+    ContactsReadonly,
+    #[scope(rename = "CONTACTS_WRITE")]
+    ContactsWriteAccess,
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(rename_all = "verbatim", display)]
+enum VerbatimScope {
+    Contacts,
+    ContactsReadonly,
+}
+
+#[test]
+fn test_rename_all() {
+    assert_eq!(ScreamingScope::ContactsReadonly, ScreamingScope::from_str("CONTACTS_READONLY").expect("Could not parse scope"));
+    assert_eq!(ScreamingScope::ContactsWriteAccess, ScreamingScope::from_str("CONTACTS_WRITE").expect("Could not parse scope"));
+    assert_eq!("CONTACTS_READONLY", ScreamingScope::ContactsReadonly.to_string());
+
+    assert_eq!(VerbatimScope::ContactsReadonly, VerbatimScope::from_str("ContactsReadonly").expect("Could not parse scope"));
+    assert_eq!("ContactsReadonly", VerbatimScope::ContactsReadonly.to_string());
+}
+
+#[test]
+#[cfg(feature = "hierarchy")]
+fn test_rename_all_hierarchy() {
+    assert!(ScreamingScope::Contacts.includes(&ScreamingScope::ContactsReadonly));
+    assert!(ScreamingScope::Contacts.includes(&ScreamingScope::ContactsWriteAccess));
+
+    assert!(VerbatimScope::Contacts.includes(&VerbatimScope::ContactsReadonly));
+    assert!(!VerbatimScope::ContactsReadonly.includes(&VerbatimScope::Contacts));
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(split = "acronyms", display)]
+enum AcronymScope {
+    Http,
+    HTTPRequest,
+    HttpV2Access,
+}
+
+#[test]
+fn test_split_acronyms() {
+    assert_eq!(AcronymScope::HTTPRequest, AcronymScope::from_str("http.request").expect("Could not parse scope"));
+    assert_eq!(AcronymScope::HttpV2Access, AcronymScope::from_str("http.v2.access").expect("Could not parse scope"));
+    assert_eq!("http.request", AcronymScope::HTTPRequest.to_string());
+}
+
+#[test]
+#[cfg(feature = "hierarchy")]
+fn test_split_acronyms_hierarchy() {
+    assert!(AcronymScope::Http.includes(&AcronymScope::HTTPRequest));
+    assert!(AcronymScope::Http.includes(&AcronymScope::HttpV2Access));
+    assert!(!AcronymScope::HTTPRequest.includes(&AcronymScope::HttpV2Access));
+}
+
+// Display is only generated with the `display` option, so it can be implemented manually
+#[derive(Debug, PartialEq, Scope)]
+enum CustomDisplayScope {
+    ProfileRead,
+}
+
+impl std::fmt::Display for CustomDisplayScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Read your profile")
+    }
+}
+
+#[test]
+fn test_custom_display() {
+    assert_eq!(CustomDisplayScope::ProfileRead, CustomDisplayScope::from_str("profile.read").expect("Could not parse scope"));
+    assert_eq!("Read your profile", CustomDisplayScope::ProfileRead.to_string());
+}
@@ -0,0 +1,189 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+
+#[cfg(feature = "hierarchy")]
+use scopes_rs::hierarchy::Hierarchized;
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(separator = ":", display)]
+enum RepoScope {
+    Repo,
+    #[scope(rename = "repo:{0}")]
+    RepoId(String),
+    #[scope(rename = "repo:{0}:read")]
+    RepoRead(String),
+    #[scope(rename = "repo:{0}:issues:{1}")]
+    RepoIssue(String, u32),
+    #[scope(rename = "repo:main:read")]
+    MainRead,
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(prefix = "api/", case_insensitive, display)]
+enum OrgScope {
+    #[scope(rename = "org.{slug}")]
+    Org { slug: String },
+    #[scope(rename = "org.{slug}.admin")]
+    OrgAdmin { slug: String },
+    #[scope(rename = "team.{team}.org.{slug}")]
+    Team { slug: String, team: u64 },
+}
+
+#[test]
+fn test_parse_parameterized() {
+    assert_eq!(RepoScope::RepoId("scopes-rs".to_string()), RepoScope::from_str("repo:scopes-rs").expect("Could not parse scope"));
+    assert_eq!(RepoScope::RepoRead("scopes-rs".to_string()), RepoScope::from_str("repo:scopes-rs:read").expect("Could not parse scope"));
+    assert_eq!(RepoScope::RepoIssue("scopes-rs".to_string(), 42), RepoScope::from_str("repo:scopes-rs:issues:42").expect("Could not parse scope"));
+
+    // Names without parameters are matched first
+    assert_eq!(RepoScope::MainRead, RepoScope::from_str("repo:main:read").expect("Could not parse scope"));
+    assert_eq!(RepoScope::Repo, RepoScope::from_str("repo").expect("Could not parse scope"));
+
+    assert!(RepoScope::from_str("repo::read").is_err());
+    assert!(RepoScope::from_str("repo:scopes-rs:write").is_err());
+    assert!(RepoScope::from_str("repo:scopes-rs:issues:abc").is_err());
+    assert!(RepoScope::from_str("repo:scopes-rs:read:more").is_err());
+}
+
+#[test]
+fn test_parse_named_fields() {
+    assert_eq!(OrgScope::Org { slug: "acme".to_string() }, OrgScope::from_str("api/org.acme").expect("Could not parse scope"));
+    assert_eq!(OrgScope::Team { slug: "acme".to_string(), team: 3 }, OrgScope::from_str("api/team.3.org.acme").expect("Could not parse scope"));
+
+    // Parameters keep their case when parsing is case-insensitive
+    assert_eq!(OrgScope::OrgAdmin { slug: "Acme".to_string() }, OrgScope::from_str(" API/Org.Acme.ADMIN ").expect("Could not parse scope"));
+
+    assert!(OrgScope::from_str("org.acme").is_err());
+}
+
+#[test]
+fn test_display_parameterized() {
+    assert_eq!("repo", RepoScope::Repo.to_string());
+    assert_eq!("repo:scopes-rs:read", RepoScope::RepoRead("scopes-rs".to_string()).to_string());
+    assert_eq!("repo:scopes-rs:issues:42", RepoScope::RepoIssue("scopes-rs".to_string(), 42).to_string());
+    assert_eq!("api/team.3.org.acme", OrgScope::Team { slug: "acme".to_string(), team: 3 }.to_string());
+
+    let scope = RepoScope::RepoIssue("scopes-rs".to_string(), 7);
+    assert_eq!(scope, RepoScope::from_str(&scope.to_string()).expect("Could not parse displayed scope"));
+}
+
+#[derive(Debug, PartialEq)]
+struct Version(String);
+
+impl FromStr for Version {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Version(s.replace('-', ".")))
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(display)]
+enum ReleaseScope {
+    #[scope(rename = "release.{0}")]
+    Release(Version),
+}
+
+#[test]
+fn test_display_round_trip() {
+    let scopes = [
+        RepoScope::Repo,
+        RepoScope::RepoId("scopes-rs".to_string()),
+        RepoScope::RepoRead("a.b".to_string()),
+        RepoScope::RepoIssue("scopes-rs".to_string(), 42),
+        RepoScope::MainRead,
+    ];
+
+    for scope in scopes {
+        assert_eq!(scope, RepoScope::from_str(&scope.to_string()).expect("Could not parse displayed scope"));
+    }
+
+    // A value containing the separator is not displayed as a single label
+    let scope = RepoScope::RepoRead("a:b".to_string());
+    assert_eq!("repo:a:b:read", scope.to_string());
+    assert!(RepoScope::from_str(&scope.to_string()).is_err());
+
+    // Parsed values displayed with the separator are rejected
+    assert_eq!(ReleaseScope::Release(Version("2".to_string())), ReleaseScope::from_str("release.2").expect("Could not parse scope"));
+    assert!(ReleaseScope::from_str("release.2-1").is_err());
+}
+
+#[test]
+fn test_enumerate_parameterized() {
+    assert_eq!(RepoScope::ALL, &[RepoScope::Repo, RepoScope::MainRead]);
+    assert_eq!(RepoScope::COUNT, RepoScope::ALL.len());
+    assert!(RepoScope::ALL.iter().enumerate().all(|(i, s)| s.index() == i));
+    assert_eq!(RepoScope::from_index(1), Some(RepoScope::MainRead));
+    assert_eq!(RepoScope::from_index(2), None);
+
+    // Parameterized scopes are indexed after the scopes of `ALL`, in declaration order
+    assert_eq!(RepoScope::RepoId("scopes-rs".to_string()).index(), 2);
+    assert_eq!(RepoScope::RepoRead("scopes-rs".to_string()).index(), 3);
+    assert_eq!(RepoScope::METADATA.len(), 5);
+    assert_eq!("repo:{0}:read", RepoScope::RepoRead("scopes-rs".to_string()).metadata().name);
+    assert_eq!("repo:main:read", RepoScope::MainRead.metadata().name);
+}
+
+#[cfg(feature = "hierarchy")]
+#[test]
+fn test_hierarchy_parameterized() {
+    let repo = |id: &str| RepoScope::RepoId(id.to_string());
+    let read = |id: &str| RepoScope::RepoRead(id.to_string());
+    let issue = |id: &str, n: u32| RepoScope::RepoIssue(id.to_string(), n);
+
+    assert!(repo("a").includes(&read("a")));
+    assert!(repo("a").includes(&issue("a", 1)));
+    assert!(!repo("a").includes(&read("b")));
+    assert!(!repo("a").includes(&issue("b", 1)));
+    assert!(!read("a").includes(&repo("a")));
+    assert!(!repo("a").includes(&repo("b")));
+
+    // Scopes without parameters include every parameter value
+    assert!(RepoScope::Repo.includes(&read("a")));
+    assert!(RepoScope::Repo.includes(&RepoScope::MainRead));
+
+    // A parameter is not a literal label
+    assert!(!repo("main").includes(&RepoScope::MainRead));
+
+    let org = |slug: &str| OrgScope::Org { slug: slug.to_string() };
+    let admin = |slug: &str| OrgScope::OrgAdmin { slug: slug.to_string() };
+
+    assert!(org("acme").includes(&admin("acme")));
+    assert!(!org("acme").includes(&admin("other")));
+    assert!(!org("acme").includes(&OrgScope::Team { slug: "acme".to_string(), team: 1 }));
+}
+
+#[cfg(feature = "hierarchy")]
+#[derive(Debug, PartialEq, Scope)]
+enum ProjectScope {
+    #[scope(rename = "project.{id}", include = [DeployId])]
+    Project { id: u32 },
+    #[scope(rename = "deploy.{id}")]
+    DeployId { id: u32 },
+    #[scope(rename = "admin", include = [Project])]
+    Admin,
+    #[scope(rename = "team.{team}", include = [DeployId], allow_unbound_params)]
+    Team { team: u32 },
+}
+
+#[cfg(feature = "hierarchy")]
+#[test]
+fn test_hierarchy_include_parameterized() {
+    // Manually included scopes must have parameters with the same name and value
+    assert!(ProjectScope::Project { id: 1 }.includes(&ProjectScope::DeployId { id: 1 }));
+    assert!(!ProjectScope::Project { id: 1 }.includes(&ProjectScope::DeployId { id: 2 }));
+
+    assert!(ProjectScope::Admin.includes(&ProjectScope::Project { id: 1 }));
+    assert!(ProjectScope::Admin.includes(&ProjectScope::DeployId { id: 2 }));
+
+    // Unbound parameters include every value
+    assert!(ProjectScope::Team { team: 1 }.includes(&ProjectScope::DeployId { id: 2 }));
+}
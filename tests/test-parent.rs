@@ -0,0 +1,46 @@
+#![cfg(feature = "hierarchy")]
+
+use scopes_macros::Scope;
+use scopes_rs::{hierarchy::Hierarchized, policy::IntoPolicy};
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case")]
+enum MyScope {
+    #[scope(parent = OrgOwner)]
+    BillingManage,
+    #[scope(parent = [OrgOwner, Support])]
+    BillingRead,
+    OrgOwner,
+    Support,
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case")]
+enum IncludeScope {
+    BillingManage,
+    BillingRead,
+    #[scope(include = [BillingManage, BillingRead])]
+    OrgOwner,
+    #[scope(include = BillingRead)]
+    Support,
+}
+
+#[test]
+fn test_parent() {
+    assert!(MyScope::OrgOwner.includes(&MyScope::BillingManage));
+    assert!(MyScope::OrgOwner.includes(&MyScope::BillingRead));
+    assert!(MyScope::Support.includes(&MyScope::BillingRead));
+    assert!(!MyScope::Support.includes(&MyScope::BillingManage));
+    assert!(!MyScope::BillingManage.includes(&MyScope::OrgOwner));
+
+    assert!(MyScope::BillingRead.into_policy().verify(&[MyScope::Support]));
+}
+
+#[test]
+fn test_parent_same_as_include() {
+    for (scope, include_scope) in MyScope::ALL.iter().zip(IncludeScope::ALL) {
+        for (other, include_other) in MyScope::ALL.iter().zip(IncludeScope::ALL) {
+            assert_eq!(include_scope.includes(include_other), scope.includes(other));
+        }
+    }
+}
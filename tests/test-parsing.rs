@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(prefix = "api/", case_insensitive, display)]
+enum CaseInsensitiveScope {
+    Foo,
+    FooBar,
+    #[scope(alias = "Legacy.Baz")]
+    Baz,
+}
+
+// Replace legacy ":" separators with "."
+fn replace_colons(s: &str) -> String {
+    s.replace(':', ".")
+}
+
+#[derive(Debug, PartialEq, Scope)]
+#[scope(normalize = replace_colons)]
+enum NormalizedScope {
+    Foo,
+    FooBar,
+}
+
+#[test]
+fn test_case_insensitive() {
+    assert_eq!(CaseInsensitiveScope::Foo, CaseInsensitiveScope::from_str("api/foo").expect("Could not parse scope"));
+    assert_eq!(CaseInsensitiveScope::Foo, CaseInsensitiveScope::from_str("API/FOO").expect("Could not parse scope"));
+    assert_eq!(CaseInsensitiveScope::FooBar, CaseInsensitiveScope::from_str("  Api/Foo.Bar\t").expect("Could not parse scope"));
+    assert_eq!(CaseInsensitiveScope::Baz, CaseInsensitiveScope::from_str("api/legacy.baz").expect("Could not parse alias"));
+
+    assert!(CaseInsensitiveScope::from_str("api/foo bar").is_err());
+
+    // The error contains the original input
+    let err = CaseInsensitiveScope::from_str(" API/Unknown").expect_err("Parsing an unknown scope should fail");
+    assert_eq!(" API/Unknown", err.0);
+
+    // Display still uses the canonical name
+    assert_eq!("api/foo.bar", CaseInsensitiveScope::FooBar.to_string());
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(NormalizedScope::Foo, NormalizedScope::from_str("foo").expect("Could not parse scope"));
+    assert_eq!(NormalizedScope::FooBar, NormalizedScope::from_str("foo:bar").expect("Could not parse scope"));
+    assert_eq!(NormalizedScope::FooBar, NormalizedScope::from_str("foo.bar").expect("Could not parse scope"));
+
+    // Case sensitivity is kept
+    assert!(NormalizedScope::from_str("FOO").is_err());
+}
@@ -0,0 +1,78 @@
+use scopes_macros::Scope;
+use scopes_rs::error::ScopeRequestError;
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(rename_all = "snake_case", display)]
+enum MyScope {
+    Openid,
+    Profile,
+    #[scope(requires = Openid)]
+    OfflineAccess,
+    #[scope(requires = [Openid, Profile], conflicts_with = ReadOnly)]
+    ProfileWrite,
+    #[scope(conflicts_with = [ProfileWrite, MyScope::Admin])]
+    ReadOnly,
+    #[scope(conflicts_with = ReadOnly)]
+    Admin,
+    #[scope(rename = "repo_{0}", conflicts_with = Admin)]
+    Repo(u32),
+}
+
+#[test]
+fn test_valid_request() {
+    assert_eq!(Ok(()), MyScope::validate_request(&[]));
+    assert_eq!(Ok(()), MyScope::validate_request(&[MyScope::Openid, MyScope::OfflineAccess]));
+    assert_eq!(Ok(()), MyScope::validate_request(&[MyScope::Profile, MyScope::ProfileWrite, MyScope::Openid]));
+    assert_eq!(Ok(()), MyScope::validate_request(&[MyScope::ReadOnly, MyScope::Repo(1)]));
+}
+
+#[test]
+fn test_missing_requirement() {
+    let request = [MyScope::OfflineAccess, MyScope::ProfileWrite, MyScope::Profile];
+
+    assert_eq!(
+        Err(vec![
+            ScopeRequestError::MissingRequirement { scope: &request[0], required: MyScope::Openid },
+            ScopeRequestError::MissingRequirement { scope: &request[1], required: MyScope::Openid },
+        ]),
+        MyScope::validate_request(&request),
+    );
+
+    let errors = MyScope::validate_request(&request[..1]).unwrap_err();
+    assert_eq!("scope 'offline_access' requires scope 'openid'", errors[0].to_string());
+}
+
+#[test]
+fn test_conflicts() {
+    let request = [MyScope::Admin, MyScope::Repo(2), MyScope::ReadOnly];
+
+    // Conflicts declared on both scopes are only reported once
+    assert_eq!(
+        Err(vec![
+            ScopeRequestError::Conflict { scope: &request[2], conflicting: &request[0] },
+            ScopeRequestError::Conflict { scope: &request[1], conflicting: &request[0] },
+        ]),
+        MyScope::validate_request(&request),
+    );
+
+    let request = [MyScope::ReadOnly, MyScope::ProfileWrite, MyScope::Openid, MyScope::Profile];
+    let errors = MyScope::validate_request(&request).unwrap_err();
+    assert_eq!(vec!["scope 'profile_write' conflicts with scope 'read_only'"], errors.iter().map(ToString::to_string).collect::<Vec<_>>());
+}
+
+#[cfg(feature = "hierarchy")]
+#[derive(Clone, Debug, PartialEq, Scope)]
+enum HierarchyScope {
+    Files,
+    FilesRead,
+    #[scope(requires = FilesRead)]
+    Export,
+}
+
+#[cfg(feature = "hierarchy")]
+#[test]
+fn test_requirement_hierarchy() {
+    // Requirements are satisfied by scopes including them
+    assert_eq!(Ok(()), HierarchyScope::validate_request(&[HierarchyScope::Export, HierarchyScope::Files]));
+    assert!(HierarchyScope::validate_request(&[HierarchyScope::Export]).is_err());
+}
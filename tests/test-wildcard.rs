@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use scopes_macros::Scope;
+use scopes_rs::{policy::{IntoPolicy, Policy}, wildcard::{Grant, WildcardScope}};
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(wildcard)]
+enum MyScope {
+    Files,
+    FilesRead,
+    FilesWrite,
+    FilesystemAdmin,
+    Admin,
+}
+
+#[derive(Clone, Debug, PartialEq, Scope)]
+#[scope(wildcard, separator = ":", prefix = "api/", case_insensitive)]
+enum RepoScope {
+    #[scope(rename = "repo:{0}:read")]
+    RepoRead(String),
+    #[scope(rename = "repo:{0}:write")]
+    RepoWrite(String),
+    #[scope(rename = "user")]
+    User,
+}
+
+fn grants<S: WildcardScope>(grants: &[&str]) -> Vec<Grant<S>> {
+    grants.iter().map(|g| Grant::from_str(g).ok().expect("Could not parse grant")).collect()
+}
+
+#[test]
+fn test_parse_grants() {
+    assert_eq!(Grant::Scope(MyScope::FilesRead), Grant::from_str("files.read").expect("Could not parse grant"));
+
+    let Grant::Wildcard(wildcard) = Grant::<MyScope>::from_str("files.*").expect("Could not parse grant") else {
+        panic!("Expected a wildcard");
+    };
+    assert_eq!(wildcard.labels(), &["files"]);
+
+    let Grant::Wildcard(wildcard) = Grant::<MyScope>::from_str("*").expect("Could not parse grant") else {
+        panic!("Expected a wildcard");
+    };
+    assert!(wildcard.labels().is_empty());
+
+    assert!(Grant::<MyScope>::from_str("files.*.read").is_err());
+    assert!(Grant::<MyScope>::from_str("files*").is_err());
+
+    // Wildcards are parsed after the prefix
+    assert!(Grant::<RepoScope>::from_str("API/Repo:*").is_ok());
+    assert!(Grant::<RepoScope>::from_str("repo:*").is_err());
+}
+
+#[test]
+fn test_wildcard_matches() {
+    let files: Vec<Grant<MyScope>> = grants(&["files.*"]);
+
+    assert!(files[0].grants(&MyScope::Files));
+    assert!(files[0].grants(&MyScope::FilesRead));
+    assert!(files[0].grants(&MyScope::FilesWrite));
+    assert!(!files[0].grants(&MyScope::FilesystemAdmin));
+    assert!(!files[0].grants(&MyScope::Admin));
+
+    let all: Vec<Grant<MyScope>> = grants(&["*"]);
+    assert!(MyScope::ALL.iter().all(|s| all[0].grants(s)));
+}
+
+#[test]
+fn test_wildcard_parameterized() {
+    let repo: Vec<Grant<RepoScope>> = grants(&["api/repo:scopes-rs:*"]);
+
+    assert!(repo[0].grants(&RepoScope::RepoRead("scopes-rs".to_string())));
+    assert!(repo[0].grants(&RepoScope::RepoWrite("scopes-rs".to_string())));
+    assert!(!repo[0].grants(&RepoScope::RepoRead("other".to_string())));
+    assert!(!repo[0].grants(&RepoScope::User));
+}
+
+#[test]
+fn test_wildcard_case_insensitive() {
+    // The prefix and literal labels ignore the case, but parameters keep it like when parsing the scope
+    let alice: Vec<Grant<RepoScope>> = grants(&[" API/Repo:alice:*"]);
+    assert!(alice[0].grants(&RepoScope::RepoRead("alice".to_string())));
+    assert!(!alice[0].grants(&RepoScope::RepoRead("ALICE".to_string())));
+
+    let exact: Vec<Grant<RepoScope>> = grants(&["api/repo:alice:read"]);
+    assert!(!exact[0].grants(&RepoScope::RepoRead("ALICE".to_string())));
+
+    let user: Vec<Grant<RepoScope>> = grants(&["api/USER:*"]);
+    assert!(user[0].grants(&RepoScope::User));
+}
+
+#[test]
+fn test_verify_grants() {
+    let policy: Policy<MyScope> = MyScope::FilesRead.into_policy() & MyScope::FilesWrite;
+
+    assert!(policy.verify_grants(&grants(&["files.*"])));
+    assert!(policy.verify_grants(&grants(&["files.read", "files.write"])));
+    assert!(!policy.verify_grants(&grants(&["files.read"])));
+    assert!(!policy.verify_grants(&grants(&["admin", "filesystem.*"])));
+
+    let policy = !MyScope::Admin.into_policy();
+    assert!(policy.verify_grants(&grants(&["files.*"])));
+    assert!(!policy.verify_grants(&grants(&["*"])));
+}